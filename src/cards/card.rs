@@ -1,3 +1,5 @@
+use std::fmt;
+use std::str::FromStr;
 use thiserror::Error;
 // Enumeration similar to C
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)] // Rust attribute, implements trats in the struct/enum following it
@@ -31,7 +33,7 @@ impl Card {
     // Similar to a constructor, take in suit and rank and returns a card. Result is an enum used to return either Ok() or Err()
     pub fn new(suit: Suit, rank: u8) -> Result<Self, CardError> {
         // Check to see if the rank is valid, if it isn't it returns an error
-        if rank < 2 || rank > 14 {
+        if !(2..=14).contains(&rank) {
             return Err(CardError::InvalidCardRank(rank));
         }
         // If the card is valid return the card, last expression in a function is implictly returned
@@ -47,11 +49,68 @@ impl Card {
     }
 }
 
+// Prints a card in the short form used in hand histories, rank then suit, e.g. As, Td, 7h
+impl fmt::Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rank = match self.rank {
+            14 => 'A',
+            13 => 'K',
+            12 => 'Q',
+            11 => 'J',
+            10 => 'T',
+            rank => (b'0' + rank) as char,
+        };
+        let suit = match self.suit {
+            Suit::Hearts => 'h',
+            Suit::Diamonds => 'd',
+            Suit::Clubs => 'c',
+            Suit::Spades => 's',
+        };
+        write!(f, "{}{}", rank, suit)
+    }
+}
+
+// Parses the short form back into a card, "As".parse::<Card>()
+impl FromStr for Card {
+    type Err = CardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.trim().chars();
+        let (Some(rank), Some(suit), None) = (chars.next(), chars.next(), chars.next()) else {
+            return Err(CardError::InvalidCardString(s.to_string()));
+        };
+        let rank = match rank.to_ascii_uppercase() {
+            'A' => 14,
+            'K' => 13,
+            'Q' => 12,
+            'J' => 11,
+            'T' => 10,
+            digit @ '2'..='9' => digit as u8 - b'0',
+            _ => return Err(CardError::InvalidCardString(s.to_string())),
+        };
+        let suit = match suit.to_ascii_lowercase() {
+            'h' => Suit::Hearts,
+            'd' => Suit::Diamonds,
+            'c' => Suit::Clubs,
+            's' => Suit::Spades,
+            _ => return Err(CardError::InvalidCardString(s.to_string())),
+        };
+        Card::new(suit, rank)
+    }
+}
+
 // Custom errors for the deck
 #[derive(Debug, Error)]
 pub enum CardError {
     // Attribure from thiserror crate
     #[error("Invalid card rank: {0}")] //Custom error message, {n} represent the nth arguement returned by the error method
     InvalidCardRank(u8), // Holds a integer, numbers not in [2,14]
+    #[error("Invalid card: {0}")]
+    InvalidCardString(String), // Text that is not a rank followed by a suit
+}
+
+// Parses a space separated list of cards, "As Kd 7h"
+pub fn parse_cards(s: &str) -> Result<Vec<Card>, CardError> {
+    s.split_whitespace().map(str::parse).collect()
 }
 
@@ -1,9 +1,11 @@
 use crate::cards::card::{Card, Suit, CardError};
 
 use rand::seq::SliceRandom;
+use rand::Rng;
 use thiserror::Error;
 
-// Basic structure of a Deck
+// Basic structure of a Deck, cloning a deck keeps the order so a game state can be copied with it
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Deck {
     cards: Vec<Card>
 }
@@ -47,6 +49,29 @@ impl Deck {
         self.shuffle();
         Ok(())
     }
+    // Initializes and shuffles the deck with the given random number generator, a seeded one gives the same deck every time
+    pub fn initialize_and_shuffle_with<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Result<(), DeckError> {
+        self.initialize()?;
+        self.cards.shuffle(rng);
+        Ok(())
+    }
+    // Shuffles the cards in the deck with the given random number generator, used for the small decks of research games
+    pub fn shuffle_with<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        self.cards.shuffle(rng);
+    }
+    // Builds a deck from cards in the order given, the last card is drawn first (used for set up hands in tests)
+    pub fn from_cards(cards: Vec<Card>) -> Self {
+        Deck { cards }
+    }
+    // Deck that deals the given cards in the order written, for setting up hands in tests
+    // Panics on a card that can't be read or is listed twice, since a card dealt twice would make the hand impossible
+    #[cfg(test)]
+    pub fn stacked(cards: &str) -> Self {
+        let mut dealt = crate::cards::card::parse_cards(cards).unwrap();
+        assert!(dealt.iter().enumerate().all(|(i, card)| !dealt[..i].contains(card)), "{} has a card twice", cards);
+        dealt.reverse();
+        Deck { cards: dealt }
+    }
     // Draws a card
     pub fn draw(&mut self) -> Result<Card, DeckError> {
         self.cards.pop().ok_or(DeckError::NotEnoughCards)
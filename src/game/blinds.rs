@@ -0,0 +1,55 @@
+// Optional blind raise made before the cards are dealt, twice the big blind
+// The action before the flop starts with the player to the left of the straddler, who acts last
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Straddle {
+    UnderTheGun, // Posted by the player after the big blind
+    Button,      // Posted by the button (a Mississippi straddle), the small blind acts first
+}
+
+// Blinds posted by players outside the normal small and big blind for one hand
+// A player who joins a game can post a big blind to be dealt in straight away instead of waiting for the big blind to reach them,
+// and a player coming back after missing blinds posts a live big blind and the small blind as dead money
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Posts {
+    pub live: Vec<usize>, // Seats posting a big blind that counts towards their bet, they still get their option
+    pub dead: Vec<usize>, // Seats posting a small blind that goes in the pot without counting towards their bet
+    pub straddle: Option<Straddle>,
+}
+
+impl Posts {
+    // No extra blinds, only the small and big blind are posted
+    pub fn none() -> Self {
+        Posts::default()
+    }
+
+    // Big blinds posted by the given seats
+    pub fn live(seats: Vec<usize>) -> Self {
+        Posts { live: seats, ..Posts::default() }
+    }
+
+    // Same posts with dead small blinds from the given seats
+    pub fn with_dead(mut self, seats: Vec<usize>) -> Self {
+        self.dead = seats;
+        self
+    }
+
+    // Same posts with a straddle
+    pub fn with_straddle(mut self, straddle: Straddle) -> Self {
+        self.straddle = Some(straddle);
+        self
+    }
+}
+
+// Blinds a player missed while sitting out, they are owed when the player is next dealt in
+// A missed big blind is posted live and a missed small blind dead, a new player posting to come in owes a big blind
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MissedBlinds {
+    pub small: bool,
+    pub big: bool,
+}
+
+impl MissedBlinds {
+    pub fn any(&self) -> bool {
+        self.small || self.big
+    }
+}
@@ -0,0 +1,57 @@
+use std::cell::Cell;
+use std::fmt;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+// Tells the game how much time has passed, so blind levels and action timers can be tested without waiting
+pub trait Clock: fmt::Debug {
+    // Time since the clock started
+    fn elapsed(&self) -> Duration;
+}
+
+// The real clock, started when it is created
+#[derive(Debug)]
+pub struct SystemClock {
+    start: Instant,
+}
+
+impl SystemClock {
+    pub fn new() -> Self {
+        SystemClock { start: Instant::now() }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        SystemClock::new()
+    }
+}
+
+impl Clock for SystemClock {
+    fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+// A clock that only moves when told to, clones share the same time so a copy can be kept to move it on
+#[derive(Debug, Clone, Default)]
+pub struct ManualClock {
+    elapsed: Rc<Cell<Duration>>,
+}
+
+impl ManualClock {
+    pub fn new() -> Self {
+        ManualClock::default()
+    }
+
+    // Moves the time on
+    pub fn advance(&self, by: Duration) {
+        self.elapsed.set(self.elapsed.get() + by);
+    }
+}
+
+impl Clock for ManualClock {
+    fn elapsed(&self) -> Duration {
+        self.elapsed.get()
+    }
+}
@@ -0,0 +1,128 @@
+use crate::game::rake::Rake;
+use crate::game::variant::{ForcedBets, PokerVariant};
+
+// How much can be bet
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BettingStructure {
+    NoLimit,  // Anything from the minimum raise up to all in
+    PotLimit, // Up to the size of the pot after calling
+    // Fixed bet and raise sizes, the small bet on the early streets and the big bet later,
+    // cap is the most bets and raises allowed on one street (the opening bet counts, a bring-in does not)
+    FixedLimit { small_bet: u32, big_bet: u32, cap: u32 },
+}
+
+// Rules a hand is played with
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameConfig {
+    pub variant: PokerVariant,
+    pub structure: BettingStructure,
+    pub small_blind: u32,
+    pub big_blind: u32, // Also the smallest bet in no limit and pot limit
+    pub ante: u32,      // Posted by every player before the cards are dealt, 0 for none
+    pub bring_in: u32,  // Forced bet of the lowest card showing in stud, 0 for none
+    pub rake: Option<Rake>, // Taken from every pot before it is paid out, None for a game without rake
+    pub boards: usize,  // Boards dealt, each playing for an equal share of the pot, 2 for double board games
+    pub bomb_pot: u32,  // Ante everyone posts instead of the blinds in a bomb pot, the betting then starts on the flop, 0 for a normal hand
+}
+
+impl GameConfig {
+    pub fn new(variant: PokerVariant, small_blind: u32, big_blind: u32) -> Self {
+        GameConfig { variant, structure: BettingStructure::NoLimit, small_blind, big_blind, ante: 0, bring_in: 0, rake: None, boards: 1, bomb_pot: 0 }
+    }
+
+    // Stud games have an ante and a bring-in instead of blinds and are played fixed limit
+    pub fn stud(variant: PokerVariant, ante: u32, bring_in: u32, small_bet: u32, big_bet: u32) -> Self {
+        GameConfig {
+            variant,
+            structure: BettingStructure::FixedLimit { small_bet, big_bet, cap: 4 },
+            small_blind: 0,
+            big_blind: 0,
+            ante,
+            bring_in,
+            rake: None,
+            boards: 1,
+            bomb_pot: 0,
+        }
+    }
+
+    // Any variant played fixed limit at small_bet/big_bet, with the forced bets the variant uses:
+    // blinds of half a small bet and a small bet, or in stud an ante of a tenth and a bring-in of a quarter of a small bet
+    pub fn limit(variant: PokerVariant, small_bet: u32, big_bet: u32) -> Self {
+        let structure = BettingStructure::FixedLimit { small_bet, big_bet, cap: 4 };
+        match variant.forced_bets() {
+            ForcedBets::Blinds => GameConfig::new(variant, (small_bet / 2).max(1), small_bet).with_structure(structure),
+            ForcedBets::BringIn => GameConfig::stud(variant, (small_bet / 10).max(1), (small_bet / 4).max(1), small_bet, big_bet),
+            ForcedBets::Antes => GameConfig::new(variant, 0, 0).with_ante(small_bet).with_structure(structure),
+        }
+    }
+
+    // Kuhn poker: everyone antes 1 and there is a single bet of 1 with no raises
+    pub fn kuhn() -> Self {
+        GameConfig {
+            variant: PokerVariant::Kuhn,
+            structure: BettingStructure::FixedLimit { small_bet: 1, big_bet: 1, cap: 1 },
+            small_blind: 0,
+            big_blind: 0,
+            ante: 1,
+            bring_in: 0,
+            rake: None,
+            boards: 1,
+            bomb_pot: 0,
+        }
+    }
+
+    // Leduc hold'em: everyone antes 1, bets and raises are 2 before the board card and 4 after, at most two a round
+    pub fn leduc() -> Self {
+        GameConfig {
+            variant: PokerVariant::Leduc,
+            structure: BettingStructure::FixedLimit { small_bet: 2, big_bet: 4, cap: 2 },
+            small_blind: 0,
+            big_blind: 0,
+            ante: 1,
+            bring_in: 0,
+            rake: None,
+            boards: 1,
+            bomb_pot: 0,
+        }
+    }
+
+    // Same rules with an ante
+    pub fn with_ante(mut self, ante: u32) -> Self {
+        self.ante = ante;
+        self
+    }
+
+    // Same rules with the house taking rake
+    pub fn with_rake(mut self, rake: Rake) -> Self {
+        self.rake = Some(rake);
+        self
+    }
+
+    // Same rules with the board dealt boards times, the best hand on each board wins its share of the pot
+    pub fn with_boards(mut self, boards: usize) -> Self {
+        self.boards = boards;
+        self
+    }
+
+    // Same rules played as a bomb pot: everyone antes amount, there are no blinds and play starts on the flop
+    pub fn with_bomb_pot(mut self, amount: u32) -> Self {
+        self.bomb_pot = amount;
+        self
+    }
+
+    // Same rules with another betting structure
+    pub fn with_structure(mut self, structure: BettingStructure) -> Self {
+        self.structure = structure;
+        self
+    }
+
+    // Size of a bet on the given street, the fixed bet in limit games and the big blind otherwise
+    pub fn bet_size(&self, street: usize) -> u32 {
+        match self.structure {
+            BettingStructure::FixedLimit { small_bet, big_bet, .. } => {
+                if street >= self.variant.big_bet_street() { big_bet } else { small_bet }
+            }
+            _ => self.big_blind,
+        }
+    }
+}
@@ -0,0 +1,294 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+use crate::cards::card::Card;
+use crate::game::observation::Observation;
+use crate::game::variant::PokerVariant;
+use crate::players::action::Action;
+use crate::rank::evaluator::combinations;
+use crate::rank::handrank::{Hand, LowHand};
+
+// Number of two card starting hands in hold'em
+const STARTING_HANDS: f64 = 1326.0;
+
+// Hands a player is thought to hold, as the best share of hold'em starting hands
+// Only hold'em hands are ranked, in every other game any hand is in the range
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Range {
+    min_score: Option<f64>, // Fewest Chen points a hand in the range has, None for any hand
+}
+
+impl Range {
+    pub fn any() -> Self {
+        Range { min_score: None }
+    }
+
+    // The best share of starting hands by Chen points, e.g. 0.2 for the top 20%, hands tied with the last one are in it too
+    pub fn top(share: f64) -> Self {
+        if share >= 1.0 {
+            return Range::any();
+        }
+        let mut classes = starting_hand_classes();
+        classes.sort_by(|a, b| b.0.total_cmp(&a.0));
+        let wanted = share.max(0.0) * STARTING_HANDS;
+        let mut hands = 0.0;
+        for (score, combos) in classes {
+            hands += combos;
+            if hands >= wanted {
+                return Range { min_score: Some(score) };
+            }
+        }
+        Range::any()
+    }
+
+    pub fn contains(&self, variant: PokerVariant, hole: &[Card]) -> bool {
+        match (self.min_score, variant, hole) {
+            (Some(min), PokerVariant::TexasHoldem, [first, second]) => chen_score(*first, *second) >= min,
+            _ => true,
+        }
+    }
+
+    // Every hand in the range that can be made from the cards, None when any hand is in it
+    fn hands(&self, variant: PokerVariant, cards: &[Card]) -> Option<Vec<Vec<Card>>> {
+        if self.min_score.is_none() || variant != PokerVariant::TexasHoldem {
+            return None;
+        }
+        Some(combinations(cards, 2).into_iter().filter(|hole| self.contains(variant, hole)).collect())
+    }
+}
+
+impl Default for Range {
+    fn default() -> Self {
+        Range::any()
+    }
+}
+
+// Chen points of a hold'em starting hand, from -1 for 72 offsuit to 20 for aces
+// Points for the high card (ace 10, king 8, queen 7, jack 6, half the rank below), doubled for a pair (at least 5),
+// 2 more when suited, less for the gap between the cards, 1 more for connected cards below a queen, halves rounded up
+pub fn chen_score(first: Card, second: Card) -> f64 {
+    let (high, low) = if first.rank() >= second.rank() { (first.rank(), second.rank()) } else { (second.rank(), first.rank()) };
+    chen_points(high, low, first.suit() == second.suit())
+}
+
+fn chen_points(high: u8, low: u8, suited: bool) -> f64 {
+    let card_points = |rank: u8| match rank {
+        14 => 10.0,
+        13 => 8.0,
+        12 => 7.0,
+        11 => 6.0,
+        _ => rank as f64 / 2.0,
+    };
+    let mut points = card_points(high);
+    if high == low {
+        return (points * 2.0).max(5.0).ceil();
+    }
+    if suited {
+        points += 2.0;
+    }
+    let gap = high - low - 1;
+    points -= match gap {
+        0 => 0.0,
+        1 => 1.0,
+        2 => 2.0,
+        3 => 4.0,
+        _ => 5.0,
+    };
+    if gap <= 1 && high < 12 {
+        points += 1.0;
+    }
+    points.ceil()
+}
+
+// Chen points of every kind of starting hand with how many ways it can be dealt
+fn starting_hand_classes() -> Vec<(f64, f64)> {
+    let mut classes = Vec::with_capacity(169);
+    for high in 2..=14 {
+        for low in 2..=high {
+            if high == low {
+                classes.push((chen_points(high, low, false), 6.0));
+            } else {
+                classes.push((chen_points(high, low, true), 4.0));
+                classes.push((chen_points(high, low, false), 12.0));
+            }
+        }
+    }
+    classes
+}
+
+// Deals the cards a player can't see: the face down cards of the other seats, each in hand holding a hand from their range (by seat)
+// and the ones who folded any cards, with the rest of the cards left to come off the deck
+#[derive(Debug, Clone)]
+pub struct CardSampler {
+    observer: usize,
+    num_seats: usize,
+    hole_cards: Vec<Card>,                     // The observer's own face down cards
+    unseen: Vec<Card>,                         // Every card the observer hasn't seen
+    seats: Vec<(usize, Option<Vec<Vec<Card>>>)>, // Other seats that were dealt in, with the hands in their range when it isn't any hand
+}
+
+impl CardSampler {
+    // None when the game has no deck to deal from
+    pub fn new<R: Rng + ?Sized>(observation: &Observation, ranges: &[Range], rng: &mut R) -> Option<Self> {
+        let variant = observation.config.variant;
+        let mut deck = variant.shuffled_deck(rng).ok()?;
+        let seen: Vec<Card> = observation.hole_cards.iter().chain(observation.up_cards.iter().flatten()).chain(observation.boards.iter().flatten()).copied().collect();
+        let mut unseen = Vec::new();
+        while let Ok(card) = deck.draw() {
+            if !seen.contains(&card) {
+                unseen.push(card);
+            }
+        }
+        // Seats that sat the hand out are folded without ever having folded
+        let dealt = |seat: usize| !observation.folded[seat] || observation.history.iter().any(|record| record.seat == seat && record.action == Action::Fold);
+        // Seats still in the hand get their cards first so the folded ones can't take the hands of their ranges
+        let mut others: Vec<usize> = (0..observation.folded.len()).filter(|&seat| seat != observation.seat && dealt(seat)).collect();
+        others.sort_by_key(|&seat| observation.folded[seat]);
+        let seats = others
+            .into_iter()
+            .map(|seat| {
+                let range = if observation.folded[seat] { None } else { ranges.get(seat).and_then(|range| range.hands(variant, &unseen)) };
+                (seat, range)
+            })
+            .collect();
+        Some(CardSampler { observer: observation.seat, num_seats: observation.folded.len(), hole_cards: observation.hole_cards.clone(), unseen, seats })
+    }
+
+    // The face down cards of every seat (the observer's own, none for seats sitting out) and the rest of the cards shuffled
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> (Vec<Vec<Card>>, Vec<Card>) {
+        let mut pool = self.unseen.clone();
+        let mut hands = vec![Vec::new(); self.num_seats];
+        for (seat, range) in &self.seats {
+            // A hand from the range that nobody else has been dealt, or any cards if none is found
+            let in_range = range.as_ref().and_then(|range| {
+                (0..50).filter_map(|_| range.choose(rng)).find(|hand| hand.iter().all(|card| pool.contains(card))).cloned()
+            });
+            let hole = in_range.unwrap_or_else(|| pool.choose_multiple(rng, self.hole_cards.len()).copied().collect());
+            pool.retain(|card| !hole.contains(card));
+            hands[*seat] = hole;
+        }
+        hands[self.observer] = self.hole_cards.clone();
+        pool.shuffle(rng);
+        (hands, pool)
+    }
+}
+
+// Share of the pot the observing player can expect at the showdown against everyone still in the hand,
+// estimated from samples deals of the cards they can't see, each opponent holding a hand from their range (by seat)
+// The cards still to come are dealt out and every board plays for its share, draws are played as standing pat
+pub fn estimate_equity<R: Rng + ?Sized>(observation: &Observation, ranges: &[Range], samples: usize, rng: &mut R) -> f64 {
+    let variant = observation.config.variant;
+    let opponents: Vec<usize> = observation.in_hand().into_iter().filter(|&seat| seat != observation.seat).collect();
+    if opponents.is_empty() {
+        return 1.0;
+    }
+    let Some(sampler) = CardSampler::new(observation, ranges, rng) else {
+        return 0.0;
+    };
+    // Cards each player and each board still get
+    let to_come = &variant.streets()[(observation.street + 1).min(variant.streets().len())..];
+    let player_cards: usize = to_come.iter().map(|street| street.down + street.up).sum();
+    let board_cards: usize = to_come.iter().map(|street| street.board).sum();
+
+    let mut total = 0.0;
+    for _ in 0..samples.max(1) {
+        let (hole, mut pool) = sampler.sample(rng);
+        let mut hands: Vec<Vec<Card>> = std::iter::once(observation.seat)
+            .chain(opponents.iter().copied())
+            .map(|seat| [hole[seat].as_slice(), &observation.up_cards[seat]].concat())
+            .collect();
+        for hand in hands.iter_mut() {
+            hand.extend((0..player_cards).filter_map(|_| pool.pop()));
+        }
+        let mut share = 0.0;
+        for board in &observation.boards {
+            let mut board = board.clone();
+            board.extend((0..board_cards).filter_map(|_| pool.pop()));
+            share += showdown_share(variant, &hands, &board);
+        }
+        total += share / observation.boards.len().max(1) as f64;
+    }
+    total / samples.max(1) as f64
+}
+
+// Likely hands of every seat read from the betting in hold'em: the top quarter for players who bet or raised, the top half for players who called
+pub fn ranges_from_betting(observation: &Observation) -> Vec<Range> {
+    (0..observation.folded.len())
+        .map(|seat| {
+            let actions = observation.history.iter().filter(|record| record.seat == seat).map(|record| record.action);
+            let mut range = Range::any();
+            for action in actions {
+                range = match action {
+                    Action::Bet(_) | Action::Raise(_) | Action::AllIn(_) => Range::top(0.25),
+                    Action::Call if range == Range::any() => Range::top(0.5),
+                    _ => range,
+                };
+            }
+            range
+        })
+        .collect()
+}
+
+// Share of the pot the first of the hands wins at a showdown on the board, split ties and the low half in hi/lo games
+fn showdown_share(variant: PokerVariant, hands: &[Vec<Card>], board: &[Card]) -> f64 {
+    let highs: Vec<Option<Hand>> = hands.iter().map(|cards| variant.best_hand(cards, board)).collect();
+    let lows: Vec<Option<LowHand>> = hands.iter().map(|cards| variant.best_low(cards, board)).collect();
+    let high = share_of_best(&highs);
+    let low = share_of_best(&lows);
+    match (high, low) {
+        (Some(high), Some(low)) => (high + low) / 2.0,
+        (Some(share), None) | (None, Some(share)) => share,
+        (None, None) => 0.0,
+    }
+}
+
+// Share of the first hand when the best of them split, None when nobody has a hand
+fn share_of_best<T: Ord + Copy>(hands: &[Option<T>]) -> Option<f64> {
+    let best = hands.iter().flatten().max()?;
+    let winners = hands.iter().filter(|hand| hand.as_ref() == Some(best)).count();
+    Some(if hands[0].as_ref() == Some(best) { 1.0 / winners as f64 } else { 0.0 })
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::card::parse_cards;
+    use crate::test_util::holdem;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn chen(cards: &str) -> f64 {
+        let cards = parse_cards(cards).unwrap();
+        chen_score(cards[0], cards[1])
+    }
+
+    #[test]
+    fn test_chen_scores() {
+        assert_eq!(chen("As Ah"), 20.0);
+        assert_eq!(chen("Ks As"), 12.0);
+        assert_eq!(chen("Ks Ah"), 10.0);
+        assert_eq!(chen("Ts 9s"), 8.0);
+        assert_eq!(chen("2c 2d"), 5.0);
+        assert_eq!(chen("7c 2d"), -1.0);
+        // The top 2% are jacks or better and ace king suited
+        let range = Range::top(0.02);
+        assert!(range.contains(PokerVariant::TexasHoldem, &parse_cards("Js Jh").unwrap()));
+        assert!(!range.contains(PokerVariant::TexasHoldem, &parse_cards("Ts Th").unwrap()));
+        assert!(range.contains(PokerVariant::OmahaHoldem, &parse_cards("7c 2d 3h 4s").unwrap()));
+    }
+    #[test]
+    fn test_equity_against_a_range() {
+        let mut rng = StdRng::seed_from_u64(7);
+        // Queen jack wins about 60% against any hand and far less against the top 2%
+        let state = holdem(2, "Kd Qs Kc Jh 2s 7d 9c Jd 3c");
+        let observation = Observation::new(&state, 0);
+        let any = estimate_equity(&observation, &[], 2000, &mut rng);
+        assert!((0.55..0.65).contains(&any), "{}", any);
+        let tight = estimate_equity(&observation, &[Range::any(), Range::top(0.02)], 2000, &mut rng);
+        assert!(tight < 0.4, "{}", tight);
+        // Once the river is out and seat 0 has the nuts nothing can beat them
+        let state = holdem(2, "2d As 3c Ah Ad Ac 9c Jh 4c");
+        let state = [Action::Call, Action::Check, Action::Check, Action::Check, Action::Check, Action::Check].iter().fold(state, |state, &action| state.apply(action).unwrap());
+        assert_eq!(estimate_equity(&Observation::new(&state, 0), &[], 100, &mut rng), 1.0);
+    }
+}
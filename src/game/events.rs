@@ -0,0 +1,164 @@
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+use crate::cards::card::Card;
+use crate::game::state::RuleError;
+use crate::game::variant::PokerVariant;
+use crate::players::action::Action;
+use crate::rank::handrank::{Hand, LowHand};
+
+// Kind of forced bet
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Blind {
+    Ante,
+    Small,
+    Big,      // Also a big blind posted out of turn by a new or returning player
+    Straddle,
+    Dead,     // A missed small blind, in the pot but not part of the player's bet
+    BombPot,  // Everyone's ante in a bomb pot
+}
+
+impl fmt::Display for Blind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Blind::Ante => write!(f, "an ante"),
+            Blind::Small => write!(f, "the small blind"),
+            Blind::Big => write!(f, "the big blind"),
+            Blind::Straddle => write!(f, "a straddle"),
+            Blind::Dead => write!(f, "a dead small blind"),
+            Blind::BombPot => write!(f, "the bomb pot"),
+        }
+    }
+}
+
+// Something that happened in a hand, the game state keeps them in order as the hand is played
+// Seats are table seats, run is which runout of the board, or which board in games with more than one, it belongs to
+// (0 unless the board was run more than once or there are more boards)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GameEvent {
+    HandStarted { variant: PokerVariant, button: usize, stacks: Vec<u32> },
+    BlindPosted { seat: usize, blind: Blind, amount: u32 },
+    // Cards given to a player, face down cards are private to the player
+    CardsDealt { seat: usize, cards: Vec<Card>, face_up: bool },
+    ActionTaken { street: usize, seat: usize, action: Action },
+    // Community cards, cards are the new ones and board is the whole board after them
+    StreetDealt { street: usize, run: usize, cards: Vec<Card>, board: Vec<Card> },
+    Showdown { run: usize, seat: usize, cards: Vec<Card>, hand: Option<Hand>, low: Option<LowHand> },
+    // Chips a seat won from a pot (0 is the main pot), with the hand and low that won them
+    PotAwarded { run: usize, pot: usize, seat: usize, amount: u32, hand: Option<Hand>, low: Option<LowHand> },
+    RakeTaken { amount: u32 },
+    // An action the table turned down, the player is asked again until they run out of attempts
+    ActionRejected { seat: usize, action: Action, error: RuleError },
+    // A player took longer than the clock allowed, they check or fold
+    TimedOut { seat: usize },
+}
+
+// Anything that wants to follow the game: logs, user interfaces, stats trackers, hand history writers
+// A table sends every event of every hand to each of its observers in the order they happened
+pub trait Observer: fmt::Debug {
+    fn on_event(&mut self, event: &GameEvent);
+
+    // Names of the players in seat order, given before every hand
+    fn on_players(&mut self, _names: &[String]) {}
+}
+
+// A shared observer, the caller keeps a handle to read what it saw
+impl<T: Observer> Observer for Rc<RefCell<T>> {
+    fn on_event(&mut self, event: &GameEvent) {
+        self.borrow_mut().on_event(event);
+    }
+
+    fn on_players(&mut self, names: &[String]) {
+        self.borrow_mut().on_players(names);
+    }
+}
+
+// Keeps every event it is sent
+#[derive(Debug, Default)]
+pub struct EventLog {
+    events: Vec<GameEvent>,
+}
+
+impl EventLog {
+    pub fn new() -> Self {
+        EventLog::default()
+    }
+
+    pub fn events(&self) -> &[GameEvent] {
+        &self.events
+    }
+}
+
+impl Observer for EventLog {
+    fn on_event(&mut self, event: &GameEvent) {
+        self.events.push(event.clone());
+    }
+}
+
+// Prints the hand as it is played, face down cards are never printed
+#[derive(Debug, Default)]
+pub struct ConsoleObserver {
+    names: Vec<String>,
+}
+
+impl ConsoleObserver {
+    pub fn new() -> Self {
+        ConsoleObserver::default()
+    }
+
+    fn name(&self, seat: usize) -> String {
+        self.names.get(seat).cloned().unwrap_or_else(|| format!("Seat {}", seat + 1))
+    }
+}
+
+impl Observer for ConsoleObserver {
+    fn on_players(&mut self, names: &[String]) {
+        self.names = names.to_vec();
+    }
+
+    fn on_event(&mut self, event: &GameEvent) {
+        match event {
+            GameEvent::HandStarted { variant, button, .. } => println!("New hand of {}, {} has the button", variant, self.name(*button)),
+            GameEvent::BlindPosted { seat, blind, amount } => println!("{} posts {} of {}", self.name(*seat), blind, amount),
+            GameEvent::CardsDealt { seat, cards, face_up: true } => println!("{} shows {}", self.name(*seat), cards_to_string(cards)),
+            GameEvent::CardsDealt { .. } => {}
+            GameEvent::ActionTaken { seat, action, .. } => println!("{} {}", self.name(*seat), action),
+            GameEvent::StreetDealt { run: 0, board, .. } => println!("Board: {}", cards_to_string(board)),
+            GameEvent::StreetDealt { run, board, .. } => println!("Board {}: {}", run + 1, cards_to_string(board)),
+            GameEvent::Showdown { seat, cards, .. } => println!("{} shows {}", self.name(*seat), cards_to_string(cards)),
+            GameEvent::PotAwarded { seat, amount, hand, low, .. } => {
+                // Name the high hand and the low that won the chips, hi/lo pots can be won with either or both
+                let shown: Vec<String> = hand.map(|hand| hand.to_string()).into_iter().chain(low.map(|low| low.to_string())).collect();
+                if shown.is_empty() {
+                    println!("{} wins {}", self.name(*seat), amount);
+                } else {
+                    println!("{} wins {} with {}", self.name(*seat), amount, shown.join(" and "));
+                }
+            }
+            GameEvent::RakeTaken { amount } => println!("Rake {}", amount),
+            GameEvent::ActionRejected { seat, error, .. } => println!("{} can't do that: {}", self.name(*seat), error),
+            GameEvent::TimedOut { seat } => println!("{} ran out of time", self.name(*seat)),
+        }
+    }
+}
+
+fn cards_to_string(cards: &[Card]) -> String {
+    cards.iter().map(|card| card.to_string()).collect::<Vec<String>>().join(" ")
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shared_observer_keeps_the_events() {
+        let log = Rc::new(RefCell::new(EventLog::new()));
+        let mut observers: Vec<Box<dyn Observer>> = vec![Box::new(log.clone()), Box::new(ConsoleObserver::new())];
+        let event = GameEvent::RakeTaken { amount: 3 };
+        for observer in observers.iter_mut() {
+            observer.on_event(&event);
+        }
+        assert_eq!(log.borrow().events(), &[event]);
+    }
+}
@@ -0,0 +1,136 @@
+use std::fmt;
+use thiserror::Error;
+
+// Why chips moved between a player's stack, the pot and the house
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChipMoveKind {
+    Ante,        // Stack to pot
+    Blind,       // Stack to pot, straddles and dead blinds included
+    Bet,         // Stack to pot: calls, bets, raises, bring-ins and all ins
+    UncalledBet, // Pot back to the stack of the player whose bet nobody called
+    Award,       // Pot to the stack of a winner
+    Rake,        // Pot to the house
+}
+
+// One movement of chips, seat is None for the rake
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChipMove {
+    pub kind: ChipMoveKind,
+    pub seat: Option<usize>,
+    pub amount: u32,
+}
+
+impl fmt::Display for ChipMove {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let who = self.seat.map_or(String::from("house"), |seat| format!("seat {}", seat));
+        write!(f, "{:?} {} ({})", self.kind, self.amount, who)
+    }
+}
+
+// Every movement of chips in a hand, in order, starting from the stacks the hand was dealt with
+// The stacks and pot it adds up to can be checked against the game so chips are never made or lost without a record
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChipLedger {
+    starting: Vec<u32>,
+    moves: Vec<ChipMove>,
+}
+
+impl ChipLedger {
+    pub fn new(starting: Vec<u32>) -> Self {
+        ChipLedger { starting, moves: Vec::new() }
+    }
+
+    // Records chips moving, zero amounts are left out
+    pub fn record(&mut self, kind: ChipMoveKind, seat: Option<usize>, amount: u32) {
+        if amount > 0 {
+            self.moves.push(ChipMove { kind, seat, amount });
+        }
+    }
+
+    pub fn moves(&self) -> &[ChipMove] {
+        &self.moves
+    }
+
+    // Chips every seat started the hand with
+    pub fn starting(&self) -> &[u32] {
+        &self.starting
+    }
+
+    // Chips a seat should have according to the ledger
+    pub fn stack(&self, seat: usize) -> i64 {
+        self.moves.iter().filter(|m| m.seat == Some(seat)).fold(self.starting[seat] as i64, |stack, m| match m.kind {
+            ChipMoveKind::Ante | ChipMoveKind::Blind | ChipMoveKind::Bet => stack - m.amount as i64,
+            ChipMoveKind::UncalledBet | ChipMoveKind::Award => stack + m.amount as i64,
+            ChipMoveKind::Rake => stack,
+        })
+    }
+
+    // Chips in the middle according to the ledger, 0 once the pot has been paid out
+    pub fn pot(&self) -> i64 {
+        self.moves.iter().fold(0, |pot, m| match m.kind {
+            ChipMoveKind::Ante | ChipMoveKind::Blind | ChipMoveKind::Bet => pot + m.amount as i64,
+            ChipMoveKind::UncalledBet | ChipMoveKind::Award | ChipMoveKind::Rake => pot - m.amount as i64,
+        })
+    }
+
+    // Chips the house has taken
+    pub fn rake(&self) -> u32 {
+        self.moves.iter().filter(|m| m.kind == ChipMoveKind::Rake).map(|m| m.amount).sum()
+    }
+
+    // Checks the stacks and pot of the game against the ledger, every move takes chips from one place and puts them in another
+    // so if they match, the stacks, pot and rake add up to the chips the hand started with
+    pub fn audit(&self, stacks: &[u32], pot: u32) -> Result<(), LedgerError> {
+        for (seat, &actual) in stacks.iter().enumerate() {
+            let expected = self.stack(seat);
+            if expected != actual as i64 {
+                return Err(LedgerError::StackMismatch { seat, expected, actual });
+            }
+        }
+        if self.pot() != pot as i64 {
+            return Err(LedgerError::PotMismatch { expected: self.pot(), actual: pot });
+        }
+        Ok(())
+    }
+}
+
+// Ways the chips in a game can disagree with the ledger
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum LedgerError {
+    #[error("Seat {seat} has {actual} chips but the ledger says {expected}")]
+    StackMismatch { seat: usize, expected: i64, actual: u32 },
+    #[error("The pot has {actual} chips but the ledger says {expected}")]
+    PotMismatch { expected: i64, actual: u32 },
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ledger_adds_up() {
+        let mut ledger = ChipLedger::new(vec![100, 100]);
+        ledger.record(ChipMoveKind::Blind, Some(0), 1);
+        ledger.record(ChipMoveKind::Blind, Some(1), 2);
+        ledger.record(ChipMoveKind::Bet, Some(0), 9);
+        ledger.record(ChipMoveKind::Bet, Some(1), 8);
+        assert_eq!(ledger.pot(), 20);
+        assert_eq!(ledger.audit(&[90, 90], 20), Ok(()));
+        ledger.record(ChipMoveKind::Rake, None, 1);
+        ledger.record(ChipMoveKind::Award, Some(1), 19);
+        ledger.record(ChipMoveKind::Award, Some(0), 0);
+        assert_eq!(ledger.moves().len(), 6);
+        assert_eq!(ledger.stack(1), 109);
+        assert_eq!(ledger.rake(), 1);
+        assert_eq!(ledger.audit(&[90, 109], 0), Ok(()));
+    }
+    #[test]
+    fn test_audit_finds_missing_chips() {
+        let mut ledger = ChipLedger::new(vec![100, 100]);
+        ledger.record(ChipMoveKind::Bet, Some(0), 10);
+        assert_eq!(ledger.audit(&[90, 95], 10), Err(LedgerError::StackMismatch { seat: 1, expected: 100, actual: 95 }));
+        assert_eq!(ledger.audit(&[90, 100], 5), Err(LedgerError::PotMismatch { expected: 10, actual: 5 }));
+        assert_eq!(ledger.audit(&[90, 100], 10), Ok(()));
+    }
+}
@@ -0,0 +1,99 @@
+use crate::players::action::{Action, Discards};
+
+// Smallest and largest total a bet or raise can be, amounts are the player's total for the round
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BetRange {
+    pub min: u32,
+    pub max: u32,
+}
+
+impl BetRange {
+    pub fn contains(&self, amount: u32) -> bool {
+        (self.min..=self.max).contains(&amount)
+    }
+}
+
+// Everything the player to act is allowed to do right now
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LegalActions {
+    pub seat: usize,             // Seat of the player to act
+    pub can_check: bool,         // Nothing to call
+    pub call: Option<u32>,       // Chips it costs to call, None if there is nothing to call
+    pub bet: Option<BetRange>,   // Opening bet range when nobody has bet yet
+    pub raise: Option<BetRange>, // Raise range when facing a bet and the betting is open to this player
+    pub all_in: Option<u32>,     // Total for the round if the player pushes all their chips in
+    pub bring_in: Option<u32>,   // Forced bring-in in stud, the player has to bet this or complete to a full bet
+    pub draw: Option<usize>,     // Cards in hand during a draw, the player has to discard some of them (or stand pat) and can't bet
+}
+
+impl LegalActions {
+    // Folding is always allowed when betting, except for the player who has to bring it in
+    pub fn can_fold(&self) -> bool {
+        self.bring_in.is_none() && self.draw.is_none()
+    }
+
+    // What a player who runs out of time does: checks if they can, otherwise folds
+    // A player who has to bring it in brings it in, and a player who has to draw stands pat
+    pub fn default_action(&self) -> Action {
+        if self.draw.is_some() {
+            Action::Draw(Discards::none())
+        } else if self.can_check {
+            Action::Check
+        } else if let Some(amount) = self.bring_in {
+            Action::Bet(amount)
+        } else {
+            Action::Fold
+        }
+    }
+
+    // Whether the action is one of the legal ones
+    pub fn contains(&self, action: &Action) -> bool {
+        match *action {
+            Action::Fold => self.can_fold(),
+            Action::Check => self.can_check,
+            Action::Call => self.call.is_some(),
+            Action::Bet(amount) => self.bet.is_some_and(|range| range.contains(amount)) || self.bring_in == Some(amount),
+            Action::Raise(amount) => self.raise.is_some_and(|range| range.contains(amount)),
+            Action::AllIn(amount) => self.all_in == Some(amount),
+            Action::Draw(discards) => self.draw.is_some_and(|cards| discards.positions().iter().all(|&position| position < cards)),
+        }
+    }
+
+    // Lists the legal actions, bets and raises are given by their smallest and largest size
+    // (a largest size that puts the player all in is listed as AllIn only), in a draw every way of discarding is listed
+    // Folding when checking is free is legal but never better, so it is left out
+    pub fn actions(&self) -> Vec<Action> {
+        if let Some(cards) = self.draw {
+            return Discards::all(cards).into_iter().map(Action::Draw).collect();
+        }
+        let mut actions = Vec::new();
+        if self.can_fold() && !self.can_check {
+            actions.push(Action::Fold);
+        }
+        if let Some(amount) = self.bring_in {
+            actions.push(Action::Bet(amount));
+        }
+        if self.can_check {
+            actions.push(Action::Check);
+        }
+        if self.call.is_some() {
+            actions.push(Action::Call);
+        }
+        if let Some(range) = self.bet.filter(|range| Some(range.min) != self.bring_in) {
+            actions.push(Action::Bet(range.min));
+            if range.max != range.min && Some(range.max) != self.all_in {
+                actions.push(Action::Bet(range.max));
+            }
+        }
+        if let Some(range) = self.raise {
+            actions.push(Action::Raise(range.min));
+            if range.max != range.min && Some(range.max) != self.all_in {
+                actions.push(Action::Raise(range.max));
+            }
+        }
+        if let Some(total) = self.all_in {
+            actions.push(Action::AllIn(total));
+        }
+        actions
+    }
+}
@@ -1 +1,20 @@
-pub mod variant;
\ No newline at end of file
+pub mod variant;
+pub mod clock;
+pub mod events;
+pub mod config;
+pub mod blinds;
+pub mod round;
+pub mod legal;
+pub mod observation;
+pub mod equity;
+pub mod ledger;
+pub mod pot;
+pub mod rake;
+pub mod rotation;
+pub mod state;
+pub mod timer;
+pub mod table;
+pub mod tournament;
+pub mod mtt;
+pub mod session;
+pub mod setup;
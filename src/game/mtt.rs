@@ -0,0 +1,426 @@
+use crate::game::clock::{Clock, SystemClock};
+use crate::game::config::GameConfig;
+use crate::game::table::Table;
+use crate::game::tournament::{standings, BlindLevel, BlindSchedule, Payouts, Standing, TournamentError};
+use crate::players::player::Player;
+use rand::Rng;
+
+// A player moved from one table to another, to balance the tables or because their table was broken
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TableMove {
+    pub entrant: usize,
+    pub from: usize,
+    pub to: usize,
+}
+
+// A tournament played over several tables
+// Players are known by their entrant number, the order they entered in, since their seat changes when they move
+// Tables are kept within one player of each other by moving the player due to be the big blind from the biggest table
+// to the smallest, a table is broken up as soon as the others have room for its players, and the last table is the final table
+// On the bubble the tables play hand for hand: every table plays one hand, then the players out in that round finish
+// by the chips they started their hand with whichever table they were at, fewer chips finish lower
+#[derive(Debug)]
+pub struct MultiTableTournament {
+    config: GameConfig,  // Rules of the hands, the blinds and ante are replaced by those of the current level
+    schedule: BlindSchedule,
+    payouts: Payouts,
+    clock: Box<dyn Clock>,
+    buy_in: u32,
+    entry_fee: u32,      // Kept by the house on top of the buy-in, tournament pots aren't raked
+    starting_stack: u32,
+    seats_per_table: usize,
+    registered: Vec<Player>, // Players waiting to be seated when the tournament starts
+    tables: Vec<Table>,
+    entrants: Vec<Vec<usize>>,   // Entrant number of the player in each seat of each table
+    num_entrants: usize,
+    rounds_played: u32,          // Rounds of one hand at every table, the level goes up by these
+    eliminated: Vec<usize>,      // Entrants in the order they went out, first out first
+    moves: Vec<TableMove>,
+}
+
+impl MultiTableTournament {
+    pub fn new(config: GameConfig, schedule: BlindSchedule, payouts: Payouts, buy_in: u32, starting_stack: u32, seats_per_table: usize) -> Self {
+        MultiTableTournament {
+            config,
+            schedule,
+            payouts,
+            clock: Box::new(SystemClock::new()),
+            buy_in,
+            entry_fee: 0,
+            starting_stack,
+            seats_per_table: seats_per_table.max(2),
+            registered: Vec::new(),
+            tables: Vec::new(),
+            entrants: Vec::new(),
+            num_entrants: 0,
+            rounds_played: 0,
+            eliminated: Vec::new(),
+            moves: Vec::new(),
+        }
+    }
+
+    // Same tournament timed by another clock
+    pub fn with_clock(mut self, clock: Box<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    // Same tournament with an entry fee paid by each player on top of the buy-in
+    pub fn with_entry_fee(mut self, fee: u32) -> Self {
+        self.entry_fee = fee;
+        self
+    }
+
+    // Registers a player, everyone is seated once the tournament starts
+    pub fn enter(&mut self, player: Player) {
+        self.registered.push(player);
+    }
+
+    // Seats the registered players over as few tables as fit them, dealing them round the tables so they are even
+    pub fn start(&mut self) -> Result<(), TournamentError> {
+        if !self.tables.is_empty() {
+            return Ok(());
+        }
+        if self.registered.len() < 2 {
+            return Err(TournamentError::NotEnoughPlayers);
+        }
+        self.num_entrants = self.registered.len();
+        let num_tables = self.num_entrants.div_ceil(self.seats_per_table);
+        self.tables = (0..num_tables).map(|_| Table::new(self.config.clone())).collect();
+        self.entrants = vec![Vec::new(); num_tables];
+        for (entrant, player) in std::mem::take(&mut self.registered).into_iter().enumerate() {
+            self.tables[entrant % num_tables].seat(player, self.starting_stack);
+            self.entrants[entrant % num_tables].push(entrant);
+        }
+        Ok(())
+    }
+
+    // Every table, broken tables stay in the list empty so table numbers never change
+    pub fn tables(&self) -> &[Table] {
+        &self.tables
+    }
+
+    // Tables that still have players
+    pub fn open_tables(&self) -> Vec<usize> {
+        (0..self.tables.len()).filter(|&table| !self.entrants[table].is_empty()).collect()
+    }
+
+    // Entrant number of the player in each seat of a table
+    pub fn entrants_at(&self, table: usize) -> &[usize] {
+        &self.entrants[table]
+    }
+
+    // Every move made between tables, in order
+    pub fn moves(&self) -> &[TableMove] {
+        &self.moves
+    }
+
+    // Entrants in the order they were knocked out
+    pub fn eliminated(&self) -> &[usize] {
+        &self.eliminated
+    }
+
+    // Players still in the tournament
+    pub fn remaining(&self) -> usize {
+        self.entrants.iter().map(|table| table.len()).sum()
+    }
+
+    // Buy-ins of everyone who entered
+    pub fn prize_pool(&self) -> u32 {
+        self.buy_in * (self.num_entrants + self.registered.len()) as u32
+    }
+
+    // Entry fees the house took from everyone who entered
+    pub fn fees(&self) -> u32 {
+        self.entry_fee * (self.num_entrants + self.registered.len()) as u32
+    }
+
+    // The blinds and ante for the next round of hands
+    pub fn level(&self) -> BlindLevel {
+        self.schedule.level(self.rounds_played, self.clock.elapsed())
+    }
+
+    // Down to one table
+    pub fn is_final_table(&self) -> bool {
+        self.open_tables().len() == 1
+    }
+
+    // One player away from the money, every table plays one hand at a time until someone goes out
+    pub fn is_hand_for_hand(&self) -> bool {
+        self.remaining() == self.payouts.places_paid() + 1
+    }
+
+    pub fn is_over(&self) -> bool {
+        !self.tables.is_empty() && self.remaining() <= 1
+    }
+
+    // Plays one hand at every table, knocks out the players who lost their chips and then balances the tables
+    // Hand for hand everyone out in the round is ranked together, a short stack busting at a later table still finishes
+    // below a bigger stack that busted at an earlier one, and players who started their hands with the same chips
+    // finish in reverse order of entry
+    pub fn play_round<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Result<(), TournamentError> {
+        self.start()?;
+        if self.is_over() {
+            return Err(TournamentError::Over);
+        }
+        let level = self.level();
+        let hand_for_hand = self.is_hand_for_hand();
+        // Players out this round, with the chips they started their last hand with
+        let mut busted: Vec<(u32, usize)> = Vec::new();
+        for table in 0..self.tables.len() {
+            if self.tables[table].players_with_chips() < 2 {
+                continue;
+            }
+            self.tables[table].set_config(GameConfig {
+                small_blind: level.small_blind,
+                big_blind: level.big_blind,
+                ante: level.ante,
+                rake: None,
+                ..self.config.clone()
+            });
+            let before = self.tables[table].stacks().to_vec();
+            self.tables[table].play_hand(rng)?;
+            let mut out: Vec<(u32, usize)> = Vec::new();
+            for seat in (0..before.len()).rev() {
+                if self.tables[table].stacks()[seat] == 0 {
+                    out.push((before[seat], self.entrants[table][seat]));
+                    self.tables[table].remove(seat);
+                    self.entrants[table].remove(seat);
+                }
+            }
+            // Away from the bubble the tables are taken one after another
+            if !hand_for_hand {
+                self.knock_out(out);
+            } else {
+                busted.extend(out);
+            }
+        }
+        self.knock_out(busted);
+        self.rounds_played += 1;
+        self.rebalance();
+        Ok(())
+    }
+
+    // Plays rounds until there is a winner and returns the final standings
+    pub fn run<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Result<Vec<Standing>, TournamentError> {
+        while !self.is_over() {
+            self.play_round(rng)?;
+        }
+        Ok(self.standings())
+    }
+
+    // Records players going out at the same time, the one who started the hand with fewer chips finishes lower
+    fn knock_out(&mut self, mut out: Vec<(u32, usize)>) {
+        out.sort_by_key(|&(chips, entrant)| (chips, std::cmp::Reverse(entrant)));
+        self.eliminated.extend(out.into_iter().map(|(_, entrant)| entrant));
+    }
+
+    // Breaks tables while the others have room for their players, then evens out the table sizes
+    fn rebalance(&mut self) {
+        loop {
+            let open = self.open_tables();
+            if open.len() < 2 || self.remaining() > (open.len() - 1) * self.seats_per_table {
+                break;
+            }
+            let Some(broken) = self.smallest_table(None) else { break };
+            while !self.entrants[broken].is_empty() {
+                let Some(to) = self.smallest_table(Some(broken)) else { break };
+                self.move_player(broken, 0, to);
+            }
+        }
+        loop {
+            let biggest = self.open_tables().into_iter().rev().max_by_key(|&table| self.entrants[table].len());
+            let (Some(biggest), Some(smallest)) = (biggest, self.smallest_table(None)) else { return };
+            if self.entrants[biggest].len() <= self.entrants[smallest].len() + 1 {
+                return;
+            }
+            let Some(seat) = self.tables[biggest].big_blind_seat() else { return };
+            self.move_player(biggest, seat, smallest);
+        }
+    }
+
+    // Takes a player with their chips from a seat at one table to the next free seat at another
+    fn move_player(&mut self, from: usize, seat: usize, to: usize) {
+        let (player, chips) = self.tables[from].remove(seat);
+        let entrant = self.entrants[from].remove(seat);
+        self.tables[to].seat(player, chips);
+        self.entrants[to].push(entrant);
+        self.moves.push(TableMove { entrant, from, to });
+    }
+
+    // Open table with the fewest players, the last one if several are as small
+    fn smallest_table(&self, except: Option<usize>) -> Option<usize> {
+        self.open_tables().into_iter().filter(|&table| Some(table) != except).rev().min_by_key(|&table| self.entrants[table].len())
+    }
+
+    // Places and prizes of the entrants who have finished, the winner included once the tournament is over
+    pub fn standings(&self) -> Vec<Standing> {
+        let winner = self.entrants.iter().flatten().copied().next().filter(|_| self.is_over());
+        standings(self.num_entrants, winner, &self.eliminated, &self.payouts.prizes(self.prize_pool()))
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::legal::LegalActions;
+    use crate::game::observation::Observation;
+    use crate::game::tournament::LevelLength;
+    use crate::game::variant::PokerVariant;
+    use crate::players::action::Action;
+    use crate::players::base::{Agent, Decision};
+    use crate::players::player::{CallStation, RandomPlayer};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    // Goes all in whenever it can, otherwise calls
+    #[derive(Debug)]
+    struct Shover;
+
+    impl Agent for Shover {
+        fn name(&self) -> &str {
+            "Shover"
+        }
+
+        fn decide(&mut self, _observation: &Observation, legal: &LegalActions) -> Decision {
+            match (legal.all_in, legal.call) {
+                (Some(total), _) => Decision::Act(Action::AllIn(total)),
+                (None, Some(_)) => Decision::Act(Action::Call),
+                _ => Decision::CheckOrFold,
+            }
+        }
+    }
+
+    // Tournament with tables of the given size and stacks of 1000, the top 3 are paid
+    // The blinds go up every 10 rounds until a hand is all in for most stacks, so even players who only call finish
+    fn tournament(seats_per_table: usize) -> MultiTableTournament {
+        let levels = vec![
+            BlindLevel { small_blind: 10, big_blind: 20, ante: 0 },
+            BlindLevel { small_blind: 50, big_blind: 100, ante: 10 },
+            BlindLevel { small_blind: 200, big_blind: 400, ante: 50 },
+            BlindLevel { small_blind: 1000, big_blind: 2000, ante: 200 },
+        ];
+        let schedule = BlindSchedule::new(levels, LevelLength::Hands(10)).unwrap();
+        let payouts = Payouts::new(vec![50, 30, 20]).unwrap();
+        MultiTableTournament::new(GameConfig::new(PokerVariant::TexasHoldem, 10, 20), schedule, payouts, 10, 1000, seats_per_table)
+    }
+
+    // Tournament with 9 handed tables started with the given number of bots,
+    // call stations for the even entrants and seeded random players for the odd ones
+    fn mtt(players: usize) -> MultiTableTournament {
+        let mut mtt = tournament(9);
+        for i in 0..players {
+            let name = format!("Player {}", i + 1);
+            if i % 2 == 0 {
+                mtt.enter(Player::new(CallStation::new().with_name(&name)));
+            } else {
+                mtt.enter(Player::new(RandomPlayer::new().with_name(&name).with_seed(i as u64)));
+            }
+        }
+        mtt.start().unwrap();
+        mtt
+    }
+
+    // Knocks out the players in the given seats of a table
+    fn bust(mtt: &mut MultiTableTournament, table: usize, seats: &[usize]) {
+        let mut seats = seats.to_vec();
+        seats.sort_unstable_by(|a, b| b.cmp(a));
+        let out = seats.iter().map(|&seat| {
+            mtt.tables[table].remove(seat);
+            (0, mtt.entrants[table].remove(seat))
+        }).collect();
+        mtt.knock_out(out);
+    }
+
+    fn sizes(mtt: &MultiTableTournament) -> Vec<usize> {
+        mtt.tables().iter().map(|table| table.players().len()).collect()
+    }
+
+    #[test]
+    fn test_players_are_spread_over_tables() {
+        let mtt = mtt(20);
+        assert_eq!(sizes(&mtt), vec![7, 7, 6]);
+        assert_eq!(mtt.entrants_at(0), &[0, 3, 6, 9, 12, 15, 18]);
+        assert_eq!(mtt.prize_pool(), 200);
+        assert_eq!(mtt.fees(), 0);
+        assert!(!mtt.is_final_table());
+    }
+    #[test]
+    fn test_balancing_moves_the_big_blind() {
+        let mut mtt = mtt(25);
+        assert_eq!(sizes(&mtt), vec![9, 8, 8]);
+        bust(&mut mtt, 2, &[0, 1, 2]);
+        let big_blind = mtt.tables[0].big_blind_seat().unwrap();
+        let entrant = mtt.entrants_at(0)[big_blind];
+        mtt.rebalance();
+        assert_eq!(sizes(&mtt), vec![7, 8, 7]);
+        assert_eq!(mtt.moves()[0], TableMove { entrant, from: 0, to: 2 });
+        assert_eq!(mtt.moves().len(), 2);
+    }
+    #[test]
+    fn test_table_is_broken_when_the_others_have_room() {
+        let mut mtt = mtt(20);
+        // 18 players left fit on two tables, the smallest table is broken up
+        bust(&mut mtt, 0, &[0, 1]);
+        mtt.rebalance();
+        assert_eq!(sizes(&mtt), vec![0, 9, 9]);
+        assert!(mtt.moves().iter().all(|m| m.from == 0));
+        assert_eq!(mtt.open_tables(), vec![1, 2]);
+        // Down to 9 the final table is formed
+        bust(&mut mtt, 1, &[0, 1, 2, 3, 4]);
+        bust(&mut mtt, 2, &[0, 1, 2, 3]);
+        mtt.rebalance();
+        assert!(mtt.is_final_table());
+        assert_eq!(mtt.remaining(), 9);
+    }
+    #[test]
+    fn test_hand_for_hand_on_the_bubble() {
+        assert!(!mtt(5).is_hand_for_hand());
+        // Four players heads up at two tables with three paid, entrants 0 and 2 at the first table have 3000 and 1 and 3 at the second 1000
+        let mut mtt = tournament(2);
+        for _ in 0..4 {
+            mtt.enter(Player::new(Shover));
+        }
+        mtt.start().unwrap();
+        assert!(mtt.is_hand_for_hand());
+        mtt.tables[0].add_chips(0, 2000);
+        mtt.tables[0].add_chips(1, 2000);
+        // Everyone is all in, so someone goes out at each table in the same round
+        mtt.play_round(&mut StdRng::seed_from_u64(1)).unwrap();
+        assert_eq!(mtt.eliminated().len(), 2);
+        // The first table played first, but its bigger stack finishes third and is paid while the short stack from the second finishes on the bubble
+        let standings = mtt.standings();
+        assert!([0, 2].contains(&standings[0].seat));
+        assert_eq!(standings[0].place, 3);
+        assert_eq!(standings[0].prize, 8);
+        assert!([1, 3].contains(&standings[1].seat));
+        assert_eq!(standings[1].place, 4);
+        assert_eq!(standings[1].prize, 0);
+        // The two left are moved to the final table
+        assert!(mtt.is_final_table());
+        assert!(!mtt.is_hand_for_hand());
+    }
+    #[test]
+    fn test_bots_play_to_a_winner() {
+        let mut mtt = mtt(20);
+        let standings = mtt.run(&mut StdRng::seed_from_u64(4)).unwrap();
+        // Everyone finishes in a different place and the winner has every chip at the final table
+        let places: Vec<usize> = standings.iter().map(|standing| standing.place).collect();
+        assert_eq!(places, (1..=20).collect::<Vec<_>>());
+        assert!(mtt.is_final_table());
+        let table = mtt.open_tables()[0];
+        assert_eq!(mtt.tables()[table].stacks().iter().sum::<u32>(), 20000);
+        assert_eq!(mtt.entrants_at(table), &[standings[0].seat]);
+        assert_eq!(standings.iter().map(|standing| standing.prize).sum::<u32>(), mtt.prize_pool());
+        // Tables were broken as players went out
+        assert!(!mtt.moves().is_empty());
+    }
+    #[test]
+    fn test_standings() {
+        let mut mtt = mtt(4);
+        bust(&mut mtt, 0, &[0]);
+        let standings = mtt.standings();
+        assert_eq!(standings, vec![Standing { seat: 0, place: 4, prize: 0 }]);
+    }
+}
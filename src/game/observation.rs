@@ -0,0 +1,124 @@
+use std::time::Duration;
+use crate::cards::card::Card;
+use crate::game::config::GameConfig;
+use crate::game::round::BettingRound;
+use crate::game::state::{ActionRecord, GameState};
+use crate::players::action::{Action, Discards};
+
+// What one player can see of a hand when it is their turn: their own cards, the face up cards and the board,
+// the chips and every action so far, but never the other players' face down cards
+// Two hands that only differ in cards the player can't see give the same observation, so it is the player's information set
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Observation {
+    pub seat: usize,                // Seat of the player observing
+    pub button: usize,
+    pub street: usize,              // Index into the variant's streets
+    pub hole_cards: Vec<Card>,      // The player's own face down cards
+    pub up_cards: Vec<Vec<Card>>,   // Face up cards of every seat (stud)
+    pub board: Vec<Card>,
+    pub boards: Vec<Vec<Card>>,     // Every board, the first is board and double board games have a second
+    pub stacks: Vec<u32>,           // Chips behind for every seat
+    pub committed: Vec<u32>,        // Chips every seat has put in the pot this hand
+    pub bets: Vec<u32>,             // Chips every seat has bet on the current street
+    pub pot: u32,                   // Chips in the middle, including the bets of the current street
+    pub folded: Vec<bool>,          // Seats that folded or sat the hand out
+    pub history: Vec<ActionRecord>, // Every action so far, other seats' draws show how many cards were thrown as the first ones
+    pub config: GameConfig,         // Rules of the game (the variant, blinds and limits), everyone at the table knows them
+    pub round: BettingRound,        // Betting on the current street: who has acted, the last raise and the raises so far
+    pub time_left: Option<Duration>, // Time the player has to decide when the table has a clock, set by the table
+}
+
+impl Observation {
+    // What the player in seat can see of the state
+    pub fn new(state: &GameState, seat: usize) -> Self {
+        let seats = 0..state.num_seats();
+        Observation {
+            seat,
+            button: state.button(),
+            street: state.street(),
+            hole_cards: state.hole_cards(seat).to_vec(),
+            up_cards: seats.clone().map(|other| state.up_cards(other).to_vec()).collect(),
+            board: state.board().to_vec(),
+            boards: state.boards().to_vec(),
+            stacks: state.stacks(),
+            committed: seats.clone().map(|other| state.committed(other)).collect(),
+            bets: seats.clone().map(|other| state.round().bet_of(other)).collect(),
+            pot: state.pot(),
+            folded: seats.map(|other| state.has_folded(other)).collect(),
+            history: state.history().iter().map(|&record| match record.action {
+                // Which of their cards another player threw away is hidden, only the number is seen
+                Action::Draw(discards) if record.seat != seat => ActionRecord { action: Action::Draw(Discards::first(discards.count())), ..record },
+                _ => record,
+            }).collect(),
+            config: state.config().clone(),
+            round: state.round().clone(),
+            time_left: None,
+        }
+    }
+
+    // The player's own cards, face down and face up
+    pub fn cards(&self) -> Vec<Card> {
+        [self.hole_cards.as_slice(), &self.up_cards[self.seat]].concat()
+    }
+
+    // Seats that have not folded
+    pub fn in_hand(&self) -> Vec<usize> {
+        (0..self.folded.len()).filter(|&seat| !self.folded[seat]).collect()
+    }
+
+    // Chips it costs the player to call
+    pub fn to_call(&self) -> u32 {
+        self.round.to_call(self.seat).min(self.stacks[self.seat])
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::card::parse_cards;
+    use crate::cards::deck::Deck;
+    use crate::game::config::GameConfig;
+    use crate::game::variant::PokerVariant;
+
+    #[test]
+    fn test_observation_hides_the_other_hole_cards() {
+        // Seat 1 gets the first card, seat 0 has the kings
+        let config = GameConfig::new(PokerVariant::TexasHoldem, 1, 2);
+        let state = GameState::new(config, vec![100, 100], 0, Deck::stacked("As Kd Ah Kc 2s 7d 9c Jh 3c")).unwrap();
+        let observation = Observation::new(&state, 0);
+        assert_eq!(observation.hole_cards, parse_cards("Kd Kc").unwrap());
+        assert_eq!(observation.bets, vec![1, 2]);
+        assert_eq!(observation.to_call(), 1);
+        assert_eq!(observation.cards(), parse_cards("Kd Kc").unwrap());
+        // Facing a raise to 6 the big blind has 4 to call, more than a stack of 3 can call
+        let raised = Observation::new(&state.apply(Action::Raise(6)).unwrap(), 1);
+        assert_eq!(raised.to_call(), 4);
+        assert_eq!(Observation { stacks: vec![94, 3], ..raised }.to_call(), 3);
+    }
+    #[test]
+    fn test_same_information_set_whatever_the_other_cards() {
+        // Only seat 1's cards differ, so seat 0 can't tell the hands apart but seat 1 can
+        let config = GameConfig::new(PokerVariant::TexasHoldem, 1, 2);
+        let aces = GameState::new(config.clone(), vec![100, 100], 0, Deck::stacked("As Kd Ah Kc 2s 7d 9c Jh 3c")).unwrap();
+        let deuces = GameState::new(config, vec![100, 100], 0, Deck::stacked("2d Kd 2h Kc 2s 7d 9c Jh 3c")).unwrap();
+        let aces = aces.apply(Action::Call).unwrap().apply(Action::Check).unwrap();
+        let deuces = deuces.apply(Action::Call).unwrap().apply(Action::Check).unwrap();
+        assert_eq!(Observation::new(&aces, 0), Observation::new(&deuces, 0));
+        assert_ne!(Observation::new(&aces, 1), Observation::new(&deuces, 1));
+        assert_eq!(Observation::new(&aces, 0).board, parse_cards("2s 7d 9c").unwrap());
+    }
+    #[test]
+    fn test_other_draws_show_only_how_many_cards() {
+        // Seat 1 throws its first and last card, seat 0 sees two cards thrown and not which
+        let config = GameConfig::new(PokerVariant::FiveCard, 1, 2);
+        let state = GameState::with_seed(config, vec![100, 100], 0, 5).unwrap();
+        let state = state.apply(Action::Call).unwrap().apply(Action::Check).unwrap();
+        let state = state.apply(Action::Draw(Discards::from_positions(&[0, 4]))).unwrap();
+        let drawn = |observation: &Observation| observation.history.last().unwrap().action;
+        assert_eq!(drawn(&Observation::new(&state, 0)), Action::Draw(Discards::first(2)));
+        assert_eq!(drawn(&Observation::new(&state, 1)), Action::Draw(Discards::from_positions(&[0, 4])));
+        assert_eq!(Discards::first(2).positions(), vec![0, 1]);
+        assert_eq!(Discards::first(8).count(), 8);
+    }
+}
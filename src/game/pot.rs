@@ -0,0 +1,131 @@
+// A main or side pot, the chips in it and the seats that can win it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pot {
+    pub amount: u32,
+    pub eligible: Vec<usize>, // Seats that have not folded and put in enough to contest this pot
+    pub winners: Vec<usize>,  // Seats that won the pot, filled in once it is awarded
+    pub low_winners: Vec<usize>, // Seats that won the low half in hi/lo games, empty if there was no qualifying low
+}
+
+impl Pot {
+    // Splits the pot evenly between the winners, odd chips go one at a time to the winners closest to the left of the button
+    pub fn split(&self, winners: &[usize], button: usize, num_seats: usize) -> Vec<(usize, u32)> {
+        split_amount(self.amount, winners, button, num_seats)
+    }
+
+    // Part of the pot played for on one of times runouts of the board, the first runout gets any odd chips
+    pub fn fraction(&self, run: usize, times: usize) -> Pot {
+        let times = times.max(1) as u32;
+        let odd_chips = if run == 0 { self.amount % times } else { 0 };
+        Pot { amount: self.amount / times + odd_chips, eligible: self.eligible.clone(), winners: Vec::new(), low_winners: Vec::new() }
+    }
+
+    // Splits a hi/lo pot, half to the best high hands and half to the best lows (the odd chip goes to the high half)
+    // Without any low winners the high hands scoop the whole pot, in lowball games there are only low winners
+    pub fn split_hi_lo(&self, button: usize, num_seats: usize) -> Vec<(usize, u32)> {
+        if self.low_winners.is_empty() {
+            return self.split(&self.winners, button, num_seats);
+        }
+        if self.winners.is_empty() {
+            return self.split(&self.low_winners, button, num_seats);
+        }
+        let low = self.amount / 2;
+        let mut shares = split_amount(self.amount - low, &self.winners, button, num_seats);
+        shares.extend(split_amount(low, &self.low_winners, button, num_seats));
+        shares
+    }
+}
+
+// Splits an amount evenly between the winners, odd chips one at a time to the winners closest to the left of the button
+fn split_amount(amount: u32, winners: &[usize], button: usize, num_seats: usize) -> Vec<(usize, u32)> {
+    if winners.is_empty() {
+        return Vec::new();
+    }
+    let mut ordered = winners.to_vec();
+    ordered.sort_by_key(|&seat| (seat + num_seats - button - 1) % num_seats);
+    let share = amount / ordered.len() as u32;
+    let odd_chips = amount as usize % ordered.len();
+    ordered
+        .iter()
+        .enumerate()
+        .map(|(i, &seat)| (seat, share + if i < odd_chips { 1 } else { 0 }))
+        .collect()
+}
+
+// Splits what every seat has committed to the hand into a main pot and side pots
+// Each all in amount of a player still in the hand closes a pot, folded chips stay in the pots they reached
+pub fn build_pots(committed: &[u32], folded: &[bool]) -> Vec<Pot> {
+    let mut levels: Vec<u32> = (0..committed.len())
+        .filter(|&seat| !folded[seat] && committed[seat] > 0)
+        .map(|seat| committed[seat])
+        .collect();
+    levels.sort_unstable();
+    levels.dedup();
+
+    let mut pots: Vec<Pot> = Vec::new();
+    let mut previous = 0;
+    for &level in &levels {
+        let amount = committed.iter().map(|&chips| chips.min(level) - chips.min(previous)).sum();
+        let eligible = (0..committed.len()).filter(|&seat| !folded[seat] && committed[seat] >= level).collect();
+        pots.push(Pot { amount, eligible, winners: Vec::new(), low_winners: Vec::new() });
+        previous = level;
+    }
+    // Chips folded above the highest amount still in the hand go to the last pot
+    let leftover: u32 = committed.iter().map(|&chips| chips.saturating_sub(previous)).sum();
+    if let Some(last) = pots.last_mut() {
+        last.amount += leftover;
+    }
+    pots
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_pot() {
+        let pots = build_pots(&[100, 100, 50], &[false, false, true]);
+        assert_eq!(pots, vec![Pot { amount: 250, eligible: vec![0, 1], winners: vec![], low_winners: vec![] }]);
+    }
+    #[test]
+    fn test_side_pots() {
+        // Seat 0 all in for 50, seat 1 all in for 120, seats 2 and 3 put in 200
+        let pots = build_pots(&[50, 120, 200, 200], &[false; 4]);
+        assert_eq!(pots.len(), 3);
+        assert_eq!((pots[0].amount, pots[0].eligible.clone()), (200, vec![0, 1, 2, 3]));
+        assert_eq!((pots[1].amount, pots[1].eligible.clone()), (210, vec![1, 2, 3]));
+        assert_eq!((pots[2].amount, pots[2].eligible.clone()), (160, vec![2, 3]));
+    }
+    #[test]
+    fn test_folded_chips_stay_in_pot() {
+        // Seat 2 put in 80 then folded, seat 0 is all in for 50
+        let pots = build_pots(&[50, 100, 80], &[false, false, true]);
+        assert_eq!((pots[0].amount, pots[0].eligible.clone()), (150, vec![0, 1]));
+        assert_eq!((pots[1].amount, pots[1].eligible.clone()), (80, vec![1]));
+    }
+    #[test]
+    fn test_split_odd_chips() {
+        // Odd chip goes to the first winner left of the button (button on seat 2)
+        let pot = Pot { amount: 101, eligible: vec![0, 1, 2], winners: vec![], low_winners: vec![] };
+        assert_eq!(pot.split(&[2, 1], 2, 3), vec![(1, 51), (2, 50)]);
+        assert_eq!(pot.split(&[0, 1], 2, 3), vec![(0, 51), (1, 50)]);
+    }
+    #[test]
+    fn test_split_hi_lo() {
+        // Seat 0 has the high, seats 1 and 2 tie for the low, the odd chip stays with the high half
+        let pot = Pot { amount: 101, eligible: vec![0, 1, 2], winners: vec![0], low_winners: vec![1, 2] };
+        assert_eq!(pot.split_hi_lo(2, 3), vec![(0, 51), (1, 25), (2, 25)]);
+        // Nobody has a low, the high scoops
+        let pot = Pot { low_winners: vec![], ..pot };
+        assert_eq!(pot.split_hi_lo(2, 3), vec![(0, 101)]);
+    }
+    #[test]
+    fn test_fraction_for_each_runout() {
+        let pot = Pot { amount: 101, eligible: vec![0, 1], winners: vec![0], low_winners: Vec::new() };
+        assert_eq!(pot.fraction(0, 2).amount, 51);
+        assert_eq!(pot.fraction(1, 2).amount, 50);
+        assert!(pot.fraction(1, 2).winners.is_empty());
+        assert_eq!(pot.fraction(0, 1).amount, 101);
+    }
+}
@@ -0,0 +1,82 @@
+// The house's share of each pot: a percentage of the pot up to a cap
+// Caps can depend on how many players were dealt in, and with no flop no drop a hand that ends before the flop isn't raked
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rake {
+    percent: u32,
+    caps: Vec<(usize, u32)>, // (players, cap) the cap applies to hands with at least that many players, in order of players
+    no_flop_no_drop: bool,
+}
+
+impl Rake {
+    // Takes percent of every pot with no cap
+    pub fn new(percent: u32) -> Self {
+        Rake { percent: percent.min(100), caps: Vec::new(), no_flop_no_drop: false }
+    }
+
+    // Same rake capped at cap chips a pot, whatever the number of players
+    pub fn with_cap(self, cap: u32) -> Self {
+        self.with_cap_for(0, cap)
+    }
+
+    // Same rake with a cap for hands dealt to at least players players, larger hands can have their own higher cap
+    pub fn with_cap_for(mut self, players: usize, cap: u32) -> Self {
+        self.caps.retain(|&(from, _)| from != players);
+        self.caps.push((players, cap));
+        self.caps.sort_unstable();
+        self
+    }
+
+    // Same rake but nothing is taken from hands that end before the flop (fourth street in stud, the first draw in draw games)
+    pub fn with_no_flop_no_drop(mut self) -> Self {
+        self.no_flop_no_drop = true;
+        self
+    }
+
+    pub fn percent(&self) -> u32 {
+        self.percent
+    }
+
+    // Cap for a hand dealt to the given number of players, None if there is no cap
+    pub fn cap(&self, players: usize) -> Option<u32> {
+        self.caps.iter().rev().find(|&&(from, _)| from <= players).map(|&(_, cap)| cap)
+    }
+
+    // Chips taken from a pot, rounded down, players is how many were dealt in and flop whether the hand got past the first street
+    pub fn amount(&self, pot: u32, players: usize, flop: bool) -> u32 {
+        if self.no_flop_no_drop && !flop {
+            return 0;
+        }
+        let rake = (pot as u64 * self.percent as u64 / 100) as u32;
+        self.cap(players).map_or(rake, |cap| rake.min(cap))
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentage_with_cap() {
+        let rake = Rake::new(5).with_cap(3);
+        assert_eq!(rake.amount(39, 6, true), 1);
+        assert_eq!(rake.amount(40, 6, true), 2);
+        assert_eq!(rake.amount(1000, 6, true), 3);
+        assert_eq!(Rake::new(5).amount(1000, 6, true), 50);
+    }
+    #[test]
+    fn test_cap_depends_on_players() {
+        let rake = Rake::new(10).with_cap_for(2, 1).with_cap_for(3, 2).with_cap_for(5, 4);
+        assert_eq!(rake.cap(2), Some(1));
+        assert_eq!(rake.cap(4), Some(2));
+        assert_eq!(rake.cap(9), Some(4));
+        assert_eq!(rake.amount(100, 2, true), 1);
+        assert_eq!(rake.amount(100, 6, true), 4);
+    }
+    #[test]
+    fn test_no_flop_no_drop() {
+        let rake = Rake::new(5).with_no_flop_no_drop();
+        assert_eq!(rake.amount(100, 6, false), 0);
+        assert_eq!(rake.amount(100, 6, true), 5);
+    }
+}
@@ -0,0 +1,153 @@
+use crate::game::config::GameConfig;
+use crate::game::variant::PokerVariant;
+use thiserror::Error;
+
+// How long each game of a mixed game is played before the next one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RotationLength {
+    Orbit,      // Once round the table, as many hands as there are players dealt in when the game starts
+    Hands(u32), // A fixed number of hands
+}
+
+// Games played in turn at a mixed game table, either in order or picked by the player on the button (dealer's choice)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rotation {
+    games: Vec<GameConfig>,
+    length: RotationLength,
+    dealers_choice: bool,
+    current: Option<usize>, // Game being played, None before the first hand
+    hands_left: u32,        // Hands left of the current game
+}
+
+impl Rotation {
+    // Plays the games in order, each for the given length, starting with the first
+    pub fn new(games: Vec<GameConfig>, length: RotationLength) -> Result<Self, RotationError> {
+        if games.is_empty() {
+            return Err(RotationError::NoGames);
+        }
+        if length == RotationLength::Hands(0) {
+            return Err(RotationError::NoHands);
+        }
+        Ok(Rotation { games, length, dealers_choice: false, current: None, hands_left: 0 })
+    }
+
+    // HORSE at small_bet/big_bet, an orbit of each: limit hold'em, Omaha hi/lo, razz, seven card stud and stud hi/lo
+    pub fn horse(small_bet: u32, big_bet: u32) -> Self {
+        let games = [
+            PokerVariant::TexasHoldem,
+            PokerVariant::OmahaHiLo,
+            PokerVariant::Razz,
+            PokerVariant::SevenCardStud,
+            PokerVariant::SevenCardStudHiLo,
+        ];
+        Rotation {
+            games: games.iter().map(|&variant| GameConfig::limit(variant, small_bet, big_bet)).collect(),
+            length: RotationLength::Orbit,
+            dealers_choice: false,
+            current: None,
+            hands_left: 0,
+        }
+    }
+
+    // Same games where the player on the button picks the next game whenever one is over
+    pub fn with_dealers_choice(mut self) -> Self {
+        self.dealers_choice = true;
+        self
+    }
+
+    pub fn games(&self) -> &[GameConfig] {
+        &self.games
+    }
+
+    pub fn length(&self) -> RotationLength {
+        self.length
+    }
+
+    pub fn is_dealers_choice(&self) -> bool {
+        self.dealers_choice
+    }
+
+    // Game being played, None before the first hand
+    pub fn current(&self) -> Option<&GameConfig> {
+        self.current.map(|game| &self.games[game])
+    }
+
+    // Whether the next hand starts a new game
+    pub fn is_changing(&self) -> bool {
+        self.hands_left == 0
+    }
+
+    // Rules for the next hand with players dealt in, moving on to the next game once the current one is over
+    // In dealer's choice choose is asked for the index of the new game, otherwise the games go in order
+    pub fn next_hand(&mut self, players: usize, choose: impl FnOnce(&[GameConfig]) -> usize) -> &GameConfig {
+        if self.hands_left == 0 {
+            let next = if self.dealers_choice {
+                choose(&self.games).min(self.games.len() - 1)
+            } else {
+                self.current.map_or(0, |game| (game + 1) % self.games.len())
+            };
+            self.current = Some(next);
+            self.hands_left = match self.length {
+                RotationLength::Orbit => players.max(1) as u32,
+                RotationLength::Hands(hands) => hands,
+            };
+        }
+        self.hands_left -= 1;
+        &self.games[self.current.unwrap_or_default()]
+    }
+}
+
+// Errors for rotations that can't be played
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum RotationError {
+    #[error("A rotation needs at least one game")]
+    NoGames,
+    #[error("Each game has to be played for at least one hand")]
+    NoHands,
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::config::BettingStructure;
+
+    #[test]
+    fn test_horse_switches_every_orbit() {
+        let mut rotation = Rotation::horse(4, 8);
+        let variants: Vec<PokerVariant> = (0..10).map(|_| rotation.next_hand(3, |_| 0).variant).collect();
+        assert_eq!(variants[..3], [PokerVariant::TexasHoldem; 3]);
+        assert_eq!(variants[3..6], [PokerVariant::OmahaHiLo; 3]);
+        assert_eq!(variants[6..9], [PokerVariant::Razz; 3]);
+        assert_eq!(variants[9], PokerVariant::SevenCardStud);
+        // Every game is limit, the flop games with blinds and the stud games with an ante and bring-in
+        let holdem = &rotation.games()[0];
+        assert_eq!((holdem.small_blind, holdem.big_blind), (2, 4));
+        assert_eq!(holdem.structure, BettingStructure::FixedLimit { small_bet: 4, big_bet: 8, cap: 4 });
+        let stud = &rotation.games()[3];
+        assert_eq!((stud.big_blind, stud.ante, stud.bring_in), (0, 1, 1));
+    }
+    #[test]
+    fn test_fixed_number_of_hands_and_wraps_around() {
+        let games = vec![GameConfig::limit(PokerVariant::TexasHoldem, 2, 4), GameConfig::limit(PokerVariant::Razz, 2, 4)];
+        let mut rotation = Rotation::new(games, RotationLength::Hands(2)).unwrap();
+        let variants: Vec<PokerVariant> = (0..5).map(|_| rotation.next_hand(6, |_| 0).variant).collect();
+        assert_eq!(variants, vec![PokerVariant::TexasHoldem, PokerVariant::TexasHoldem, PokerVariant::Razz, PokerVariant::Razz, PokerVariant::TexasHoldem]);
+        assert_eq!(Rotation::new(Vec::new(), RotationLength::Orbit), Err(RotationError::NoGames));
+    }
+    #[test]
+    fn test_dealers_choice() {
+        let mut rotation = Rotation::horse(4, 8).with_dealers_choice();
+        let mut asked = 0;
+        for hand in 0..4 {
+            let game = rotation.next_hand(2, |games| {
+                asked += 1;
+                assert_eq!(games.len(), 5);
+                if hand == 0 { 2 } else { 4 }
+            });
+            assert_eq!(game.variant, if hand < 2 { PokerVariant::Razz } else { PokerVariant::SevenCardStudHiLo });
+        }
+        // Only asked when a game was over
+        assert_eq!(asked, 2);
+    }
+}
@@ -1,9 +1,266 @@
-use crate::players::player::PlayerType;
-use std::collections::VecDeque;
-
-struct PokerRound {
-    players: VecDeque<PlayerType>,
-    current_bet: u32,
-    pot: u32,
-    active_players: usize,
-}
\ No newline at end of file
+// State of a single betting round (one street), tracks who has put in what and who may still raise
+// Seats are referred to by index, the caller is responsible for stacks and folds
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BettingRound {
+    bets: Vec<u32>,              // Chips each seat has put in on this street
+    current_bet: u32,            // Highest bet on this street, the amount everyone has to match
+    min_bet: u32,                // Smallest opening bet, normally the big blind
+    last_raise: u32,             // Size of the last full bet or raise, the minimum raise increment
+    full_bet: u32,               // Total of the last full bet or raise, a seat that acted below it may raise again
+    raises: u32,                 // Number of full bets and raises, used for the cap in limit games
+    acted_at: Vec<Option<u32>>,  // The bet each seat was facing after it last acted, None if it has not acted yet
+}
+
+impl BettingRound {
+    // Starts a new betting round for num_seats seats where the smallest bet is min_bet
+    pub fn new(num_seats: usize, min_bet: u32) -> Self {
+        BettingRound {
+            bets: vec![0; num_seats],
+            current_bet: 0,
+            min_bet,
+            last_raise: min_bet, // Even after a short blind the minimum raise is a full min_bet
+            full_bet: 0,
+            raises: 0,
+            acted_at: vec![None; num_seats],
+        }
+    }
+
+    // Posts a forced bet (blind) of amount for a seat, posting is not acting so the big blind still gets its option
+    // blind is the full size of the blind, a player all in for less still makes everyone else call the full blind
+    pub fn post(&mut self, seat: usize, amount: u32, blind: u32) {
+        self.bets[seat] += amount;
+        self.current_bet = self.current_bet.max(self.bets[seat]).max(blind);
+        // A full big blind counts as the opening bet
+        if self.current_bet >= self.min_bet && self.full_bet == 0 {
+            self.full_bet = self.current_bet;
+            self.raises = 1;
+        }
+    }
+
+    // Posts a straddle, a blind raise of a full straddle acts like a bigger big blind: the next raise has to be at least its size
+    // A player all in for less than the straddle has only posted a blind
+    pub fn straddle(&mut self, seat: usize, amount: u32, straddle: u32) {
+        self.post(seat, amount, amount);
+        if amount >= straddle {
+            self.last_raise = straddle;
+            self.full_bet = straddle;
+            self.raises += 1;
+        }
+    }
+
+    // Highest bet on the street
+    pub fn current_bet(&self) -> u32 {
+        self.current_bet
+    }
+
+    // Size of the last full bet or raise
+    pub fn last_raise(&self) -> u32 {
+        self.last_raise
+    }
+
+    // Number of full bets and raises on the street, the big blind counts as a bet and a bring-in does not
+    pub fn raises(&self) -> u32 {
+        self.raises
+    }
+
+    // Chips a seat has put in on this street
+    pub fn bet_of(&self, seat: usize) -> u32 {
+        self.bets[seat]
+    }
+
+    // Chips a seat has to add to match the current bet
+    pub fn to_call(&self, seat: usize) -> u32 {
+        self.current_bet - self.bets[seat]
+    }
+
+    // The smallest total a bet or raise has to reach, the current bet plus the last full raise increment
+    // Below a full bet (after a stud bring-in or a short all in) the bet can be completed to min_bet
+    pub fn min_raise_to(&self) -> u32 {
+        if self.current_bet < self.min_bet {
+            return self.min_bet;
+        }
+        self.current_bet + self.last_raise
+    }
+
+    // Whether a seat has acted since the last time the betting was reopened
+    pub fn has_acted(&self, seat: usize) -> bool {
+        self.acted_at[seat].is_some()
+    }
+
+    // Whether the seat may still bet or raise
+    // A seat that has already acted may only re-raise if it now faces at least a full raise,
+    // an all-in for less than a full raise does not reopen the betting (short all-ins do add up though),
+    // completing a bring-in to a full bet does
+    pub fn can_raise(&self, seat: usize) -> bool {
+        match self.acted_at[seat] {
+            None => true,
+            Some(faced) => self.current_bet - faced >= self.last_raise || faced < self.full_bet,
+        }
+    }
+
+    // Records a check or a call of amount chips, amount may be short of to_call if the seat is all in
+    pub fn call(&mut self, seat: usize, amount: u32) {
+        self.bets[seat] += amount;
+        self.acted_at[seat] = Some(self.current_bet);
+    }
+
+    // Records a bet or raise bringing the seat's total on this street to total
+    // A full raise sets a new minimum increment, a short all-in or a bring-in only raises the amount to call
+    pub fn raise_to(&mut self, seat: usize, total: u32) {
+        let increment = total.saturating_sub(self.current_bet);
+        if increment >= self.last_raise {
+            self.last_raise = increment;
+            self.full_bet = total;
+            self.raises += 1;
+        } else if self.current_bet < self.min_bet && total >= self.min_bet {
+            // Completing to a full bet, the next raise is a full min_bet on top of it
+            self.last_raise = self.min_bet;
+            self.full_bet = total;
+            self.raises += 1;
+        }
+        self.bets[seat] = total;
+        self.current_bet = self.current_bet.max(total);
+        self.acted_at[seat] = Some(self.current_bet);
+    }
+
+    // The betting round is over once every seat still able to act has acted and matched the current bet
+    // live lists the seats that have not folded and still have chips behind
+    pub fn is_complete(&self, live: &[usize]) -> bool {
+        live.iter().all(|&seat| self.has_acted(seat) && self.bets[seat] == self.current_bet)
+    }
+}
+
+// Unit tests, scenarios follow the TDA tournament rules on raises
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Blinds of 50/100 with seats 0 (small blind) and 1 (big blind)
+    fn preflop(num_seats: usize) -> BettingRound {
+        let mut round = BettingRound::new(num_seats, 100);
+        round.post(0, 50, 50);
+        round.post(1, 100, 100);
+        round
+    }
+
+    #[test]
+    fn test_min_raise_preflop_is_double_big_blind() {
+        let round = preflop(3);
+        assert_eq!(round.current_bet(), 100);
+        assert_eq!(round.min_raise_to(), 200);
+    }
+    #[test]
+    fn test_min_raise_is_previous_bet_plus_last_increment() {
+        // Raise to 350 is an increment of 250, so the next raise has to be to at least 600, not 700
+        let mut round = preflop(4);
+        round.raise_to(2, 350);
+        assert_eq!(round.last_raise(), 250);
+        assert_eq!(round.min_raise_to(), 600);
+        round.raise_to(3, 600);
+        assert_eq!(round.min_raise_to(), 850);
+    }
+    #[test]
+    fn test_opening_bet_postflop() {
+        let mut round = BettingRound::new(3, 100);
+        assert_eq!(round.min_raise_to(), 100);
+        round.raise_to(0, 250);
+        assert_eq!(round.last_raise(), 250);
+        assert_eq!(round.min_raise_to(), 500);
+    }
+    #[test]
+    fn test_short_all_in_does_not_change_min_raise() {
+        // Bet 100, all in for 150 is short of a full raise, the next raise is still 100 more than 150
+        let mut round = BettingRound::new(3, 100);
+        round.raise_to(0, 100);
+        round.raise_to(1, 150);
+        assert_eq!(round.current_bet(), 150);
+        assert_eq!(round.last_raise(), 100);
+        assert_eq!(round.min_raise_to(), 250);
+    }
+    #[test]
+    fn test_short_all_in_does_not_reopen_betting() {
+        // Seat 0 bets 100, seat 1 calls, seat 2 is all in for 150: seats 0 and 1 may only call or fold
+        let mut round = BettingRound::new(3, 100);
+        round.raise_to(0, 100);
+        round.call(1, 100);
+        round.raise_to(2, 150);
+        assert!(!round.can_raise(0));
+        assert!(!round.can_raise(1));
+        assert_eq!(round.to_call(0), 50);
+        assert!(!round.is_complete(&[0, 1]));
+    }
+    #[test]
+    fn test_short_all_in_allows_raise_for_players_yet_to_act() {
+        // Seat 0 bets 100, seat 1 is all in for 150, seat 2 has not acted and may still raise
+        let mut round = BettingRound::new(3, 100);
+        round.raise_to(0, 100);
+        round.raise_to(1, 150);
+        assert!(round.can_raise(2));
+        assert!(!round.can_raise(0));
+    }
+    #[test]
+    fn test_short_all_ins_add_up_to_reopen_betting() {
+        // Seat 0 bets 100, seat 1 all in for 150, seat 2 all in for 220: seat 0 now faces 120 more, a full raise
+        let mut round = BettingRound::new(4, 100);
+        round.raise_to(0, 100);
+        round.raise_to(1, 150);
+        round.raise_to(2, 220);
+        assert!(round.can_raise(0));
+        assert_eq!(round.min_raise_to(), 320);
+    }
+    #[test]
+    fn test_full_raise_reopens_betting() {
+        let mut round = BettingRound::new(3, 100);
+        round.raise_to(0, 100);
+        round.call(1, 100);
+        round.raise_to(2, 200);
+        assert!(round.can_raise(0));
+        assert!(round.can_raise(1));
+    }
+    #[test]
+    fn test_big_blind_keeps_option() {
+        // Everyone limps, the big blind has not acted yet so the round is not over
+        let mut round = preflop(3);
+        round.call(2, 100);
+        round.call(0, 50);
+        assert!(!round.is_complete(&[0, 1, 2]));
+        assert!(round.can_raise(1));
+        round.call(1, 0);
+        assert!(round.is_complete(&[0, 1, 2]));
+    }
+    #[test]
+    fn test_straddle_doubles_the_min_raise() {
+        let mut round = preflop(4);
+        round.straddle(2, 200, 200);
+        assert_eq!(round.current_bet(), 200);
+        assert_eq!(round.min_raise_to(), 400);
+        assert_eq!(round.raises(), 2);
+        // The straddler has posted but not acted, they keep their option
+        assert!(!round.has_acted(2));
+    }
+    #[test]
+    fn test_bring_in_can_be_completed() {
+        // Stud with a bring-in of 2 and a small bet of 10: seat 1 completes, the bring-in may then raise
+        let mut round = BettingRound::new(3, 10);
+        round.raise_to(0, 2);
+        assert_eq!(round.raises(), 0);
+        assert_eq!(round.min_raise_to(), 10);
+        round.raise_to(1, 10);
+        assert_eq!(round.raises(), 1);
+        assert_eq!(round.min_raise_to(), 20);
+        assert!(round.can_raise(0));
+        round.raise_to(2, 20);
+        assert_eq!(round.raises(), 2);
+        assert_eq!(round.to_call(0), 18);
+    }
+    #[test]
+    fn test_short_big_blind_still_plays_as_full_blind() {
+        // Big blind all in for 60, everyone else still calls 100 and the minimum raise is to 200
+        let mut round = BettingRound::new(3, 100);
+        round.post(0, 50, 50);
+        round.post(1, 60, 100);
+        assert_eq!(round.current_bet(), 100);
+        assert_eq!(round.to_call(2), 100);
+        assert_eq!(round.min_raise_to(), 200);
+    }
+}
@@ -0,0 +1,343 @@
+use crate::game::config::GameConfig;
+use crate::game::events::Observer;
+use crate::game::rotation::Rotation;
+use crate::game::state::{HandResult, RuleError};
+use crate::game::table::Table;
+use crate::game::timer::ActionTimer;
+use crate::players::player::Player;
+use rand::Rng;
+use thiserror::Error;
+
+// How a player joining a game that has already started gets dealt in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JoinOption {
+    Post,            // Posts a big blind and plays the next hand
+    WaitForBigBlind, // Sits out until the big blind comes round to them
+}
+
+// One player's money over the session, everything they put on the table and took off it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LedgerEntry {
+    pub name: String,
+    pub bought_in: u32,  // Buy-in, rebuys and top ups
+    pub cashed_out: u32, // Chips taken away when leaving
+    pub stack: u32,      // Chips on the table now, 0 once they have left
+    pub seated: bool,
+}
+
+impl LedgerEntry {
+    // Chips won (or lost if negative) so far, counting what is still on the table
+    pub fn net(&self) -> i64 {
+        self.cashed_out as i64 + self.stack as i64 - self.bought_in as i64
+    }
+}
+
+// A cash game: players come and go between hands and buy chips within the table's limits
+// Players are known by their number in the ledger, the order they joined in, since their seat changes as others leave
+#[derive(Debug)]
+pub struct Session {
+    table: Table,
+    min_buy_in: u32,
+    max_buy_in: u32,
+    ledger: Vec<LedgerEntry>,
+    seats: Vec<usize>,   // Ledger number of the player in each seat
+    waiting: Vec<usize>, // Players sitting out until they are the big blind
+    hands_played: u32,
+}
+
+impl Session {
+    pub fn new(config: GameConfig, min_buy_in: u32, max_buy_in: u32) -> Result<Self, SessionError> {
+        if min_buy_in == 0 || min_buy_in > max_buy_in {
+            return Err(SessionError::BuyInRange { min: min_buy_in, max: max_buy_in });
+        }
+        Ok(Session {
+            table: Table::new(config),
+            min_buy_in,
+            max_buy_in,
+            ledger: Vec::new(),
+            seats: Vec::new(),
+            waiting: Vec::new(),
+            hands_played: 0,
+        })
+    }
+
+    // Puts the players on the clock, players who join later get a full time bank too
+    pub fn set_timer(&mut self, timer: ActionTimer) {
+        self.table.set_timer(timer);
+    }
+
+    // Plays a mixed game from the next hand on
+    pub fn set_rotation(&mut self, rotation: Rotation) {
+        self.table.set_rotation(rotation);
+    }
+
+    // Makes the next hand a bomb pot with everyone anteing amount
+    pub fn bomb_pot(&mut self, amount: u32) {
+        self.table.bomb_pot(amount);
+    }
+
+    // Sends the events of every hand from now on to an observer
+    pub fn subscribe(&mut self, observer: Box<dyn Observer>) {
+        self.table.subscribe(observer);
+    }
+
+    pub fn table(&self) -> &Table {
+        &self.table
+    }
+
+    // Every player who has played in the session, in the order they joined
+    pub fn ledger(&self) -> &[LedgerEntry] {
+        &self.ledger
+    }
+
+    pub fn hands_played(&self) -> u32 {
+        self.hands_played
+    }
+
+    // Whether a player is sitting out until the big blind reaches them
+    pub fn is_waiting(&self, player: usize) -> bool {
+        self.waiting.contains(&player)
+    }
+
+    // Sits a new player down with a buy-in, returns their number in the ledger
+    // Before the first hand, or when there is no game going, they are simply dealt in
+    pub fn join(&mut self, player: Player, buy_in: u32, option: JoinOption) -> Result<usize, SessionError> {
+        self.check_buy_in(buy_in)?;
+        let id = self.ledger.len();
+        let game_running = self.hands_played > 0 && self.table.players_dealt_in() >= 2;
+        self.ledger.push(LedgerEntry { name: player.get_name().to_string(), bought_in: buy_in, cashed_out: 0, stack: buy_in, seated: true });
+        self.table.seat(player, buy_in);
+        self.seats.push(id);
+        let seat = self.seats.len() - 1;
+        if game_running {
+            match option {
+                JoinOption::Post => self.table.post_big_blind(seat),
+                JoinOption::WaitForBigBlind => {
+                    self.table.sit_out(seat);
+                    self.waiting.push(id);
+                }
+            }
+        }
+        Ok(id)
+    }
+
+    // Keeps a player out of the hands until they sit back in, their chips stay on the table
+    pub fn sit_out(&mut self, player: usize) -> Result<(), SessionError> {
+        let seat = self.seat_of(player)?;
+        self.table.sit_out(seat);
+        Ok(())
+    }
+
+    // Deals a player back in from the next hand
+    pub fn sit_in(&mut self, player: usize) -> Result<(), SessionError> {
+        let seat = self.seat_of(player)?;
+        self.waiting.retain(|&waiting| waiting != player);
+        self.table.sit_in(seat);
+        Ok(())
+    }
+
+    // Buys a new stack for a player who has lost all their chips, within the buy-in limits
+    pub fn rebuy(&mut self, player: usize, buy_in: u32) -> Result<(), SessionError> {
+        let seat = self.seat_of(player)?;
+        if self.table.stacks()[seat] > 0 {
+            return Err(SessionError::StillHasChips(self.table.stacks()[seat]));
+        }
+        self.check_buy_in(buy_in)?;
+        self.add_chips(player, seat, buy_in);
+        Ok(())
+    }
+
+    // Adds chips to a player's stack, the stack can't go over the maximum buy-in
+    pub fn top_up(&mut self, player: usize, chips: u32) -> Result<(), SessionError> {
+        let seat = self.seat_of(player)?;
+        let stack = self.table.stacks()[seat] + chips;
+        if stack > self.max_buy_in {
+            return Err(SessionError::AboveMaxBuyIn { stack, max: self.max_buy_in });
+        }
+        self.add_chips(player, seat, chips);
+        Ok(())
+    }
+
+    // Takes a player off the table with their chips, returns how many they left with
+    pub fn leave(&mut self, player: usize) -> Result<u32, SessionError> {
+        let seat = self.seat_of(player)?;
+        let (_, chips) = self.table.remove(seat);
+        self.seats.remove(seat);
+        self.waiting.retain(|&waiting| waiting != player);
+        let entry = &mut self.ledger[player];
+        entry.cashed_out += chips;
+        entry.stack = 0;
+        entry.seated = false;
+        Ok(chips)
+    }
+
+    // Plays a hand, players waiting for the big blind come in when it reaches them
+    pub fn play_hand<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Result<HandResult, SessionError> {
+        for player in self.waiting.clone() {
+            let seat = self.seat_of(player)?;
+            if self.table.would_be_big_blind(seat) || self.table.players_dealt_in() < 2 {
+                self.sit_in(player)?;
+            }
+        }
+        let result = self.table.play_hand(rng)?;
+        self.hands_played += 1;
+        for (seat, &player) in self.seats.iter().enumerate() {
+            self.ledger[player].stack = self.table.stacks()[seat];
+        }
+        Ok(result)
+    }
+
+    fn add_chips(&mut self, player: usize, seat: usize, chips: u32) {
+        self.table.add_chips(seat, chips);
+        self.ledger[player].bought_in += chips;
+        self.ledger[player].stack += chips;
+    }
+
+    fn check_buy_in(&self, buy_in: u32) -> Result<(), SessionError> {
+        if buy_in < self.min_buy_in {
+            return Err(SessionError::BelowMinBuyIn { amount: buy_in, min: self.min_buy_in });
+        }
+        if buy_in > self.max_buy_in {
+            return Err(SessionError::AboveMaxBuyIn { stack: buy_in, max: self.max_buy_in });
+        }
+        Ok(())
+    }
+
+    // Seat of a player still at the table
+    fn seat_of(&self, player: usize) -> Result<usize, SessionError> {
+        self.seats.iter().position(|&seated| seated == player).ok_or(SessionError::NotSeated(player))
+    }
+}
+
+// Errors managing a cash game
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum SessionError {
+    #[error("The buy-in limits {min} to {max} are not valid")]
+    BuyInRange { min: u32, max: u32 },
+    #[error("A buy-in of {amount} is below the minimum of {min}")]
+    BelowMinBuyIn { amount: u32, min: u32 },
+    #[error("A stack of {stack} would be above the maximum buy-in of {max}")]
+    AboveMaxBuyIn { stack: u32, max: u32 },
+    #[error("Can only rebuy with no chips left, there are still {0}")]
+    StillHasChips(u32),
+    #[error("Player {0} is not at the table")]
+    NotSeated(usize),
+    #[error(transparent)]
+    Rule(#[from] RuleError),
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use crate::game::events::{Blind, EventLog, GameEvent};
+    use crate::game::variant::PokerVariant;
+    use crate::players::player::CallStation;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    // Blinds 1/2 with buy-ins from 50 to 200
+    fn session() -> Session {
+        Session::new(GameConfig::new(PokerVariant::TexasHoldem, 1, 2), 50, 200).unwrap()
+    }
+
+    // Call stations, so every hand goes to a showdown and chips change hands
+    fn player(name: &str) -> Player {
+        Player::new(CallStation::new().with_name(name))
+    }
+
+    #[test]
+    fn test_buy_in_limits() {
+        assert_eq!(Session::new(GameConfig::new(PokerVariant::TexasHoldem, 1, 2), 300, 200).unwrap_err(), SessionError::BuyInRange { min: 300, max: 200 });
+        let mut session = session();
+        assert_eq!(session.join(player("A"), 40, JoinOption::Post), Err(SessionError::BelowMinBuyIn { amount: 40, min: 50 }));
+        assert_eq!(session.join(player("A"), 250, JoinOption::Post), Err(SessionError::AboveMaxBuyIn { stack: 250, max: 200 }));
+        assert_eq!(session.join(player("A"), 100, JoinOption::Post), Ok(0));
+        assert_eq!(session.ledger().len(), 1);
+    }
+    #[test]
+    fn test_join_before_the_first_hand_is_dealt_in() {
+        let mut session = session();
+        session.join(player("A"), 100, JoinOption::WaitForBigBlind).unwrap();
+        session.join(player("B"), 100, JoinOption::WaitForBigBlind).unwrap();
+        assert!(!session.is_waiting(1));
+        assert_eq!(session.table().players_dealt_in(), 2);
+    }
+    #[test]
+    fn test_join_mid_session() {
+        let mut session = session();
+        let log = Rc::new(RefCell::new(EventLog::new()));
+        session.subscribe(Box::new(log.clone()));
+        let mut rng = StdRng::seed_from_u64(6);
+        for name in ["A", "B", "C"] {
+            session.join(player(name), 100, JoinOption::Post).unwrap();
+        }
+        for _ in 0..3 {
+            session.play_hand(&mut rng).unwrap();
+        }
+        let waiting = session.join(player("D"), 100, JoinOption::WaitForBigBlind).unwrap();
+        assert!(session.is_waiting(waiting));
+        assert!(session.table().is_sitting_out(3));
+        let posting = session.join(player("E"), 100, JoinOption::Post).unwrap();
+        assert!(session.table().missed_blinds(4).big);
+        assert!(!session.table().is_sitting_out(4));
+        // The player who posted is dealt into the next hand and charged a big blind, the waiting player isn't dealt in
+        // until the big blind reaches them
+        let mut sat_in_at = None;
+        for hand in 0..10 {
+            let big_blind = session.table().would_be_big_blind(3);
+            let seen = log.borrow().events().len();
+            session.play_hand(&mut rng).unwrap();
+            let posts: Vec<GameEvent> = log.borrow().events()[seen..].iter().filter(|event| matches!(event, GameEvent::BlindPosted { .. })).cloned().collect();
+            if hand == 0 {
+                assert!(posts.contains(&GameEvent::BlindPosted { seat: 4, blind: Blind::Big, amount: 2 }));
+                assert!(!session.table().missed_blinds(4).any());
+            }
+            assert_eq!(session.is_waiting(waiting), !big_blind);
+            if big_blind {
+                assert!(posts.contains(&GameEvent::BlindPosted { seat: 3, blind: Blind::Big, amount: 2 }));
+                sat_in_at = Some(hand);
+                break;
+            }
+            assert!(posts.iter().all(|event| !matches!(event, GameEvent::BlindPosted { seat: 3, .. })));
+        }
+        assert!(sat_in_at.is_some_and(|hand| hand > 0));
+        // The ledger follows the stacks after every hand, no chips are made or lost
+        for (seat, &stack) in session.table().stacks().iter().enumerate() {
+            assert_eq!(session.ledger()[seat].stack, stack);
+        }
+        assert_eq!(session.ledger().iter().map(|entry| entry.net()).sum::<i64>(), 0);
+        // Sitting out and back in by choice
+        session.sit_out(posting).unwrap();
+        assert_eq!(session.table().players_dealt_in(), 4);
+        session.sit_in(posting).unwrap();
+        assert_eq!(session.table().players_dealt_in(), 5);
+    }
+    #[test]
+    fn test_rebuy_and_top_up() {
+        let mut session = session();
+        session.join(player("A"), 100, JoinOption::Post).unwrap();
+        assert_eq!(session.rebuy(0, 100), Err(SessionError::StillHasChips(100)));
+        assert_eq!(session.top_up(0, 150), Err(SessionError::AboveMaxBuyIn { stack: 250, max: 200 }));
+        session.top_up(0, 100).unwrap();
+        assert_eq!(session.table().stacks(), &[200]);
+        assert_eq!(session.ledger()[0].bought_in, 200);
+        assert_eq!(session.ledger()[0].net(), 0);
+    }
+    #[test]
+    fn test_leave_records_the_cash_out() {
+        let mut session = session();
+        session.join(player("A"), 100, JoinOption::Post).unwrap();
+        session.join(player("B"), 150, JoinOption::Post).unwrap();
+        session.join(player("C"), 200, JoinOption::Post).unwrap();
+        assert_eq!(session.leave(1), Ok(150));
+        assert_eq!(session.leave(1), Err(SessionError::NotSeated(1)));
+        // Seats after the one that left move up, players keep their ledger number
+        assert_eq!(session.table().stacks(), &[100, 200]);
+        assert_eq!(session.top_up(2, 10), Err(SessionError::AboveMaxBuyIn { stack: 210, max: 200 }));
+        let entry = &session.ledger()[1];
+        assert_eq!((entry.cashed_out, entry.stack, entry.seated, entry.net()), (150, 0, false, 0));
+    }
+}
@@ -0,0 +1,439 @@
+use std::fs;
+use std::path::Path;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde::Deserialize;
+use thiserror::Error;
+use crate::game::config::{BettingStructure, GameConfig};
+use crate::game::rake::Rake;
+use crate::game::session::{JoinOption, Session};
+use crate::game::table::Table;
+use crate::game::variant::{ForcedBets, PokerVariant};
+use crate::players::player::Player;
+use crate::players::registry::{AgentRegistry, AgentSpec};
+
+// A table written in a TOML (or JSON, with the same keys) file, e.g.
+//
+//   seed = 7                  # Deals the same cards every time, left out for a new shuffle every run
+//
+//   [game]
+//   variant = "holdem"        # Any game name, e.g. "omaha8", "stud", "razz", "kuhn"
+//   structure = "no-limit"    # "no-limit", "pot-limit" or "limit", stud games are always limit
+//   small_blind = 1           # Half the big blind if left out
+//   big_blind = 2
+//   ante = 0
+//   boards = 1                # 2 for double board, half the pot for the best hand on each board
+//
+//   [rake]                    # No rake if left out
+//   percent = 5
+//   cap = 3
+//   no_flop_no_drop = true
+//
+//   [buy_in]                  # Cash game limits in play mode, the smallest and largest stack if left out
+//   min = 100
+//   max = 200
+//
+//   [[seats]]                 # One per player, in seat order
+//   name = "Alice"
+//   player = "human"          # Any agent the registry knows, e.g. "random" or "cfr:strategies/leduc.bin", human if left out
+//   stack = 200
+//
+// Limit games take small_bet, big_bet (twice the small bet if left out) and cap (4), stud games ante and bring_in instead of blinds
+// Unknown keys are errors, so a misspelt key isn't silently ignored
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SetupFile {
+    seed: Option<u64>,
+    game: GameSection,
+    rake: Option<RakeSection>,
+    buy_in: Option<BuyInSection>,
+    #[serde(default)]
+    seats: Vec<SeatSection>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct GameSection {
+    variant: String,
+    structure: Option<String>,
+    small_blind: Option<u32>,
+    big_blind: Option<u32>,
+    ante: Option<u32>,
+    bring_in: Option<u32>,
+    small_bet: Option<u32>,
+    big_bet: Option<u32>,
+    cap: Option<u32>,
+    boards: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RakeSection {
+    percent: u32,
+    cap: Option<u32>,
+    #[serde(default)]
+    no_flop_no_drop: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BuyInSection {
+    min: u32,
+    max: u32,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SeatSection {
+    name: Option<String>,
+    player: Option<String>,
+    stack: u32,
+}
+
+// One seat of the table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeatSetup {
+    pub name: String,
+    pub player: String, // Agent spec for the registry, e.g. "human" or "random", see AgentSpec
+    pub stack: u32,
+}
+
+// A checked table setup, ready to be played by a simulation (table) or by people (session)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableSetup {
+    config: GameConfig,
+    seats: Vec<SeatSetup>,
+    seed: Option<u64>,
+    buy_in: (u32, u32),
+}
+
+impl TableSetup {
+    // Reads a setup file, .json files as JSON and anything else as TOML
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, SetupError> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path).map_err(|e| SetupError::Read { path: path.display().to_string(), message: e.to_string() })?;
+        if path.extension().is_some_and(|extension| extension == "json") {
+            TableSetup::from_json(&text)
+        } else {
+            TableSetup::from_toml(&text)
+        }
+    }
+
+    pub fn from_toml(text: &str) -> Result<Self, SetupError> {
+        let file = parse(toml::Deserializer::new(text), |e: &toml::de::Error| e.message().to_string())?;
+        TableSetup::check(file)
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, SetupError> {
+        let file = parse(&mut serde_json::Deserializer::from_str(text), |e: &serde_json::Error| e.to_string())?;
+        TableSetup::check(file)
+    }
+
+    // Rules of the hands
+    pub fn config(&self) -> &GameConfig {
+        &self.config
+    }
+
+    pub fn seats(&self) -> &[SeatSetup] {
+        &self.seats
+    }
+
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    // Smallest and largest buy-in in play mode
+    pub fn buy_in(&self) -> (u32, u32) {
+        self.buy_in
+    }
+
+    // Random numbers for shuffling, from the seed if there is one
+    pub fn rng(&self) -> StdRng {
+        self.seed.map_or_else(StdRng::from_entropy, StdRng::seed_from_u64)
+    }
+
+    // Same setup with the players of the first seats replaced by the given agent specs, in seat order
+    pub fn with_players(mut self, specs: &[String]) -> Result<Self, SetupError> {
+        if specs.len() > self.seats.len() {
+            return Err(invalid("seats", format!("{} players given for {} seats", specs.len(), self.seats.len())));
+        }
+        for (i, (seat, spec)) in self.seats.iter_mut().zip(specs).enumerate() {
+            spec.parse::<AgentSpec>().map_err(|e| invalid(format!("seats[{}].player", i), e))?;
+            seat.player = spec.clone();
+        }
+        Ok(self)
+    }
+
+    // A table with everyone seated with their stack, for simulations, the registry makes the players' agents
+    pub fn table(&self, registry: &AgentRegistry) -> Result<Table, SetupError> {
+        let mut table = Table::new(self.config.clone());
+        for (i, seat) in self.seats.iter().enumerate() {
+            table.seat(player(registry, i, seat)?, seat.stack);
+        }
+        Ok(table)
+    }
+
+    // A cash game with everyone bought in for their stack, for play mode
+    pub fn session(&self, registry: &AgentRegistry) -> Result<Session, SetupError> {
+        let (min, max) = self.buy_in;
+        let mut session = Session::new(self.config.clone(), min, max).map_err(|e| invalid("buy_in", e))?;
+        for (i, seat) in self.seats.iter().enumerate() {
+            session.join(player(registry, i, seat)?, seat.stack, JoinOption::Post).map_err(|e| invalid(format!("seats[{}].stack", i), e))?;
+        }
+        Ok(session)
+    }
+
+    // Checks the values in the file make a game that can be played
+    fn check(file: SetupFile) -> Result<Self, SetupError> {
+        let mut config = game_config(&file.game)?;
+        if let Some(boards) = file.game.boards {
+            if boards == 0 || (boards > 1 && !config.variant.has_board()) {
+                return Err(invalid("game.boards", format!("{} can't be played on {} boards", config.variant, boards)));
+            }
+            config = config.with_boards(boards);
+        }
+        let config = match file.rake {
+            Some(rake) => {
+                if rake.percent > 100 {
+                    return Err(invalid("rake.percent", "can't be more than 100"));
+                }
+                let mut house = Rake::new(rake.percent);
+                if let Some(cap) = rake.cap {
+                    house = house.with_cap(cap);
+                }
+                if rake.no_flop_no_drop {
+                    house = house.with_no_flop_no_drop();
+                }
+                config.with_rake(house)
+            }
+            None => config,
+        };
+        if file.seats.len() < 2 {
+            return Err(invalid("seats", "a table needs at least two seats"));
+        }
+        let max = config.variant.max_players();
+        if file.seats.len() > max {
+            return Err(invalid("seats", format!("{} is played by at most {} players", config.variant, max)));
+        }
+        let mut seats = Vec::with_capacity(file.seats.len());
+        for (i, seat) in file.seats.into_iter().enumerate() {
+            // Which agents exist is only known to the registry that makes the players, here the spec just has to be one
+            let player = seat.player.unwrap_or_else(|| String::from("human"));
+            player.parse::<AgentSpec>().map_err(|e| invalid(format!("seats[{}].player", i), e))?;
+            if seat.stack == 0 {
+                return Err(invalid(format!("seats[{}].stack", i), "has to be more than 0"));
+            }
+            seats.push(SeatSetup { name: seat.name.unwrap_or_else(|| format!("Player {}", i + 1)), player, stack: seat.stack });
+        }
+        let buy_in = match file.buy_in {
+            Some(BuyInSection { min, max }) if min == 0 || min > max => return Err(invalid("buy_in", "min has to be more than 0 and no more than max")),
+            Some(BuyInSection { min, max }) => (min, max),
+            None => (seats.iter().map(|seat| seat.stack).min().unwrap_or(1), seats.iter().map(|seat| seat.stack).max().unwrap_or(1)),
+        };
+        if let Some(i) = seats.iter().position(|seat| seat.stack < buy_in.0 || seat.stack > buy_in.1) {
+            return Err(invalid(format!("seats[{}].stack", i), format!("has to be a buy-in from {} to {}", buy_in.0, buy_in.1)));
+        }
+        Ok(TableSetup { config, seats, seed: file.seed, buy_in })
+    }
+}
+
+// Deserializes a setup file keeping track of the key being read, so a wrong value can be pointed at
+fn parse<'de, D, E>(deserializer: D, message: impl Fn(&E) -> String) -> Result<SetupFile, SetupError>
+where
+    D: serde::Deserializer<'de, Error = E>,
+    E: std::error::Error,
+{
+    serde_path_to_error::deserialize(deserializer).map_err(|e| {
+        let key = e.path().to_string();
+        // A file that isn't valid TOML or JSON at all has no key to point at
+        let key = if key == "." { String::from("file") } else { key };
+        SetupError::Invalid { key, message: message(e.inner()) }
+    })
+}
+
+// Rules from the game section, the forced bets depend on the game: blinds for flop and draw games,
+// antes and a bring-in for stud, and Kuhn and Leduc have their own fixed antes and bets
+fn game_config(game: &GameSection) -> Result<GameConfig, SetupError> {
+    let variant: PokerVariant = game.variant.parse().map_err(|e| invalid("game.variant", e))?;
+    if variant.streets().is_empty() {
+        return Err(invalid("game.variant", format!("{} can't be played yet", variant)));
+    }
+    let structure = game.structure.as_deref().map(|name| name.chars().filter(|c| c.is_ascii_alphanumeric()).collect::<String>().to_lowercase());
+    let limit = match structure.as_deref() {
+        None => variant.is_stud(),
+        Some("nolimit") | Some("potlimit") => false,
+        Some("limit") | Some("fixedlimit") => true,
+        Some(_) => return Err(invalid("game.structure", format!("unknown structure {}, expected no-limit, pot-limit or limit", game.structure.as_deref().unwrap_or_default()))),
+    };
+    if !limit {
+        for (key, value) in [("game.small_bet", game.small_bet), ("game.big_bet", game.big_bet), ("game.cap", game.cap)] {
+            if value.is_some() {
+                return Err(invalid(key, "is only used in limit games"));
+            }
+        }
+    }
+    match variant.forced_bets() {
+        ForcedBets::Antes => {
+            let set = [("game.structure", game.structure.is_some()), ("game.small_blind", game.small_blind.is_some()), ("game.big_blind", game.big_blind.is_some())];
+            if let Some((key, _)) = set.iter().find(|(_, set)| *set) {
+                return Err(invalid(*key, format!("{} has fixed antes and bets", variant)));
+            }
+            Ok(if variant == PokerVariant::Kuhn { GameConfig::kuhn() } else { GameConfig::leduc() })
+        }
+        ForcedBets::BringIn => {
+            if !limit {
+                return Err(invalid("game.structure", "stud games are played limit"));
+            }
+            for (key, value) in [("game.small_blind", game.small_blind), ("game.big_blind", game.big_blind)] {
+                if value.is_some() {
+                    return Err(invalid(key, "stud games have an ante and a bring-in instead of blinds"));
+                }
+            }
+            let small_bet = game.small_bet.filter(|&bet| bet > 0).ok_or_else(|| invalid("game.small_bet", "a limit game needs a small bet"))?;
+            let (big_bet, cap) = limit_bets(game, small_bet)?;
+            let defaults = GameConfig::limit(variant, small_bet, big_bet);
+            let config = GameConfig::stud(variant, game.ante.unwrap_or(defaults.ante), game.bring_in.unwrap_or(defaults.bring_in), small_bet, big_bet);
+            Ok(config.with_structure(BettingStructure::FixedLimit { small_bet, big_bet, cap }))
+        }
+        ForcedBets::Blinds => {
+            if game.bring_in.is_some() {
+                return Err(invalid("game.bring_in", "only stud games have a bring-in"));
+            }
+            let big_blind = game.big_blind.or(game.small_bet.filter(|_| limit)).filter(|&blind| blind > 0).ok_or_else(|| invalid("game.big_blind", "has to be more than 0"))?;
+            let small_blind = game.small_blind.unwrap_or(big_blind / 2);
+            if small_blind > big_blind {
+                return Err(invalid("game.small_blind", "can't be more than the big blind"));
+            }
+            let config = GameConfig::new(variant, small_blind, big_blind).with_ante(game.ante.unwrap_or_default());
+            if !limit {
+                let pot_limit = structure.as_deref() == Some("potlimit");
+                return Ok(config.with_structure(if pot_limit { BettingStructure::PotLimit } else { BettingStructure::NoLimit }));
+            }
+            let small_bet = game.small_bet.unwrap_or(big_blind);
+            let (big_bet, cap) = limit_bets(game, small_bet)?;
+            Ok(config.with_structure(BettingStructure::FixedLimit { small_bet, big_bet, cap }))
+        }
+    }
+}
+
+// Big bet and cap of a limit game
+fn limit_bets(game: &GameSection, small_bet: u32) -> Result<(u32, u32), SetupError> {
+    let big_bet = game.big_bet.unwrap_or(2 * small_bet);
+    if big_bet < small_bet {
+        return Err(invalid("game.big_bet", "can't be less than the small bet"));
+    }
+    let cap = game.cap.unwrap_or(4);
+    if cap == 0 {
+        return Err(invalid("game.cap", "has to allow at least one bet"));
+    }
+    Ok((big_bet, cap))
+}
+
+// The player for seat i made by the registry, an agent it can't make is an error in the seat's player key
+fn player(registry: &AgentRegistry, i: usize, seat: &SeatSetup) -> Result<Player, SetupError> {
+    registry.create(&seat.player, &seat.name).map_err(|e| invalid(format!("seats[{}].player", i), e))
+}
+
+fn invalid(key: impl Into<String>, message: impl ToString) -> SetupError {
+    SetupError::Invalid { key: key.into(), message: message.to_string() }
+}
+
+// Errors for setup files that can't be read or don't make a playable game
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum SetupError {
+    #[error("Could not read {path}: {message}")]
+    Read { path: String, message: String },
+    #[error("{key}: {message}")]
+    Invalid { key: String, message: String },
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOLDEM: &str = r#"
+        seed = 7
+
+        [game]
+        variant = "Texas Hold'em"
+        big_blind = 2
+
+        [rake]
+        percent = 5
+        cap = 3
+
+        [[seats]]
+        name = "Alice"
+        stack = 200
+
+        [[seats]]
+        stack = 100
+    "#;
+
+    // The key an error points at
+    fn key(result: Result<TableSetup, SetupError>) -> String {
+        match result {
+            Err(SetupError::Invalid { key, .. }) => key,
+            other => panic!("Expected an invalid key, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_toml_setup() {
+        let setup = TableSetup::from_toml(HOLDEM).unwrap();
+        assert_eq!(setup.config().variant, PokerVariant::TexasHoldem);
+        assert_eq!((setup.config().small_blind, setup.config().big_blind), (1, 2));
+        assert_eq!(setup.config().rake, Some(Rake::new(5).with_cap(3)));
+        assert_eq!(setup.seats()[1], SeatSetup { name: String::from("Player 2"), player: String::from("human"), stack: 100 });
+        assert_eq!(setup.buy_in(), (100, 200));
+        let registry = AgentRegistry::new();
+        assert_eq!(setup.table(&registry).unwrap().stacks(), &[200, 100]);
+        assert_eq!(setup.session(&registry).unwrap().ledger().len(), 2);
+        // Players given on the command line replace the first seats' players
+        let setup = setup.with_players(&[String::from("ai")]).unwrap();
+        assert_eq!(setup.table(&registry).unwrap().players()[0].get_name(), "Alice");
+        assert_eq!(key(setup.with_players(&vec![String::from("ai"); 3])), "seats");
+    }
+    #[test]
+    fn test_json_is_the_same_format() {
+        let json = r#"{
+            "seed": 7,
+            "game": { "variant": "Texas Hold'em", "big_blind": 2 },
+            "rake": { "percent": 5, "cap": 3 },
+            "seats": [{ "name": "Alice", "stack": 200 }, { "stack": 100 }]
+        }"#;
+        assert_eq!(TableSetup::from_json(json).unwrap(), TableSetup::from_toml(HOLDEM).unwrap());
+    }
+    #[test]
+    fn test_limit_and_stud_setups() {
+        let omaha = "[game]\nvariant = \"omaha8\"\nstructure = \"limit\"\nsmall_bet = 4\n[[seats]]\nstack = 100\n[[seats]]\nstack = 100";
+        let config = TableSetup::from_toml(omaha).unwrap().config().clone();
+        assert_eq!((config.small_blind, config.big_blind), (2, 4));
+        assert_eq!(config.structure, BettingStructure::FixedLimit { small_bet: 4, big_bet: 8, cap: 4 });
+        let razz = "[game]\nvariant = \"razz\"\nsmall_bet = 10\nante = 2\n[[seats]]\nstack = 100\n[[seats]]\nstack = 100";
+        let config = TableSetup::from_toml(razz).unwrap().config().clone();
+        assert_eq!((config.ante, config.bring_in, config.big_blind), (2, 2, 0));
+        let double_board = "[game]\nvariant = \"omaha\"\nstructure = \"pot-limit\"\nbig_blind = 2\nboards = 2\n[[seats]]\nstack = 100\n[[seats]]\nstack = 100";
+        assert_eq!(TableSetup::from_toml(double_board).unwrap().config().boards, 2);
+        assert_eq!(key(TableSetup::from_toml(&razz.replace("ante = 2", "boards = 2"))), "game.boards");
+    }
+    #[test]
+    fn test_errors_name_the_key() {
+        assert_eq!(key(TableSetup::from_toml(&HOLDEM.replace("big_blind = 2", "big_blind = \"two\""))), "game.big_blind");
+        assert_eq!(key(TableSetup::from_toml(&HOLDEM.replace("big_blind = 2", "big_blinds = 2"))), "game.big_blinds");
+        assert_eq!(key(TableSetup::from_toml(&HOLDEM.replace("stack = 100", "stack = -5"))), "seats[1].stack");
+        assert_eq!(key(TableSetup::from_toml(&HOLDEM.replace("Texas Hold'em", "Texas Fold'em"))), "game.variant");
+        assert_eq!(key(TableSetup::from_toml(&HOLDEM.replace("percent = 5", "percent = 500"))), "rake.percent");
+        assert_eq!(key(TableSetup::from_toml(&HOLDEM.replace("stack = 100", "stack = 0"))), "seats[1].stack");
+        assert_eq!(key(TableSetup::from_toml(&HOLDEM.replace("stack = 100", "stack = 100\nplayer = \":\""))), "seats[1].player");
+        // An agent the registry doesn't know is only found when the players are made
+        let robot = TableSetup::from_toml(&HOLDEM.replace("stack = 100", "stack = 100\nplayer = \"robot\"")).unwrap();
+        assert_eq!(key(robot.table(&AgentRegistry::new()).map(|_| robot.clone())), "seats[1].player");
+        assert_eq!(key(TableSetup::from_toml(&HOLDEM.replace("big_blind = 2", "big_blind = 2\nsmall_bet = 2"))), "game.small_bet");
+        assert_eq!(key(TableSetup::from_toml(&HOLDEM.replace("Texas Hold'em", "stud"))), "game.big_blind");
+        assert_eq!(key(TableSetup::from_toml("seed = ")), "file");
+        assert!(matches!(TableSetup::from_file("no/such/table.toml"), Err(SetupError::Read { .. })));
+    }
+}
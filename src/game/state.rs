@@ -0,0 +1,1664 @@
+use crate::cards::card::Card;
+use crate::cards::deck::Deck;
+use crate::cards::card::Suit;
+use crate::game::blinds::{Posts, Straddle};
+use crate::game::config::{BettingStructure, GameConfig};
+use crate::game::events::{Blind, GameEvent};
+use crate::game::ledger::{ChipLedger, ChipMoveKind, LedgerError};
+use crate::game::legal::{BetRange, LegalActions};
+use crate::game::observation::Observation;
+use crate::game::pot::{build_pots, Pot};
+use crate::game::round::BettingRound;
+use crate::players::action::{Action, Discards};
+use crate::rank::evaluator::{showing_low_strength, showing_strength};
+use crate::rank::handrank::{Hand, LowHand};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use thiserror::Error;
+
+// One seat at the table during a hand
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Seat {
+    stack: u32,      // Chips behind
+    committed: u32,  // Chips put in the pot this hand
+    folded: bool,    // Folded, or never dealt in because the seat has no chips
+    hole: Vec<Card>, // Private cards, dealt face down
+    up: Vec<Card>,   // Cards dealt face up in front of the player (stud)
+}
+
+// An action that was taken, in the order they happened
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ActionRecord {
+    pub street: usize,
+    pub seat: usize,
+    pub action: Action,
+}
+
+// How a finished hand was settled
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HandResult {
+    pub pots: Vec<Pot>,             // Main pot first, then the side pots, with their winners
+    pub winnings: Vec<u32>,         // Chips each seat won from the pots
+    pub hands: Vec<Option<Hand>>,   // Hands shown down, None for seats that folded or won without a showdown
+    pub lows: Vec<Option<LowHand>>, // Qualifying lows shown down in hi/lo games
+    pub rake: u32,                  // Chips the house took before the pots were paid out
+    // Every runout when the board was run more than once, or every board in games with more than one, each settling its share of the pots,
+    // the pots above then hold the whole pots and nobody's hand is given there, empty when there was a single board run once
+    pub runouts: Vec<Runout>,
+}
+
+// One of several boards dealt after everyone was all in, with its share of each pot and the hands shown on it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Runout {
+    pub board: Vec<Card>,
+    pub pots: Vec<Pot>,
+    pub hands: Vec<Option<Hand>>,
+    pub lows: Vec<Option<LowHand>>,
+}
+
+// Complete state of a hand: the cards, stacks, pot and betting, and whose turn it is
+// The state is a plain value, apply returns the next state and leaves this one untouched,
+// so a hand can be searched or simulated without any players or input and output
+#[derive(Debug, Clone)]
+pub struct GameState {
+    config: GameConfig,
+    seats: Vec<Seat>,
+    button: usize,
+    deck: Deck,                   // Remaining cards, in the order they will be dealt
+    boards: Vec<Vec<Card>>,       // Community cards, one board unless the game is played with more
+    street: usize,                // Index into the variant's streets
+    round: BettingRound,          // Betting on the current street
+    to_act: Option<usize>,        // Seat that has to act next, None once the hand is over
+    history: Vec<ActionRecord>,
+    result: Option<HandResult>,   // Set once the hand is over
+    to_draw: Vec<usize>,          // Seats still to draw in the current draw, in order, empty when nobody is drawing
+    muck: Vec<Card>,              // Cards thrown away in draws, shuffled back into the deck if it runs out
+    rng: StdRng,                  // Shuffles the muck, part of the state so a hand replays the same way
+    max_runouts: usize,           // Most times the board may be run when everyone is all in, 1 to always run it once
+    awaiting_runouts: bool,       // Everyone is all in and the players have to agree how many times to run the board
+    runout_boards: Vec<Vec<Card>>, // Boards dealt when run more than once
+    ledger: ChipLedger,           // Every movement of chips in the hand
+    audit: bool,                  // Check the chips against the ledger after every action
+    events: Vec<GameEvent>,       // Everything that happened in the hand, in order
+}
+
+impl GameState {
+    // Starts a hand: posts the antes and blinds and deals the first street from the given deck
+    // Seats without chips sit the hand out, the blinds are left of the button (the button is the small blind heads up)
+    // Stud games have no blinds, the lowest card showing brings it in, and games with only antes start left of the button
+    pub fn new(config: GameConfig, stacks: Vec<u32>, button: usize, deck: Deck) -> Result<Self, RuleError> {
+        GameState::with_posts(config, stacks, button, deck, &Posts::none())
+    }
+
+    // Starts a hand where some players post a blind out of turn as well, see Posts
+    pub fn with_posts(config: GameConfig, stacks: Vec<u32>, button: usize, deck: Deck, posts: &Posts) -> Result<Self, RuleError> {
+        if config.variant.streets().is_empty() {
+            return Err(RuleError::UnsupportedVariant(config.variant.to_string()));
+        }
+        if (config.boards > 1 || config.bomb_pot > 0) && !config.variant.has_board() {
+            return Err(RuleError::NoBoard(config.variant.to_string()));
+        }
+        let num_seats = stacks.len();
+        let players = stacks.iter().filter(|&&stack| stack > 0).count();
+        if players < 2 {
+            return Err(RuleError::NotEnoughPlayers);
+        }
+        if players > config.variant.max_players() {
+            return Err(RuleError::TooManyPlayers(config.variant.max_players()));
+        }
+        let ledger = ChipLedger::new(stacks.clone());
+        let boards = vec![Vec::new(); config.boards.max(1)];
+        let seats = stacks
+            .into_iter()
+            .map(|stack| Seat { stack, committed: 0, folded: stack == 0, hole: Vec::new(), up: Vec::new() })
+            .collect();
+        let mut state = GameState {
+            round: BettingRound::new(num_seats, config.bet_size(0)),
+            config,
+            seats,
+            button: button % num_seats,
+            deck,
+            boards,
+            street: 0,
+            to_act: None,
+            history: Vec::new(),
+            result: None,
+            to_draw: Vec::new(),
+            muck: Vec::new(),
+            rng: StdRng::seed_from_u64(0),
+            max_runouts: 1,
+            awaiting_runouts: false,
+            runout_boards: Vec::new(),
+            ledger,
+            audit: false,
+            events: Vec::new(),
+        };
+        state.events.push(GameEvent::HandStarted { variant: state.config.variant, button: state.button, stacks: state.stacks() });
+
+        // In a bomb pot everyone antes the same and the cards are dealt to the flop without any betting
+        if state.config.bomb_pot > 0 {
+            for seat in state.in_hand() {
+                let amount = state.config.bomb_pot.min(state.seats[seat].stack);
+                state.move_to_pot(seat, amount, ChipMoveKind::Ante);
+                state.emit_post(seat, Blind::BombPot, amount);
+            }
+            state.deal_street()?;
+            state.deal_on()?;
+            return Ok(state);
+        }
+        // Antes are dead money, they go in the pot but do not count towards the bet
+        for seat in state.in_hand() {
+            let ante = state.config.ante.min(state.seats[seat].stack);
+            state.move_to_pot(seat, ante, ChipMoveKind::Ante);
+            state.emit_post(seat, Blind::Ante, ante);
+        }
+        if state.config.variant.is_stud() || state.config.big_blind == 0 {
+            state.deal_street()?;
+            state.to_act = state.first_to_act();
+            state.settle_if_round_over()?;
+            return Ok(state);
+        }
+        // Heads up the button posts the small blind
+        let small_blind_seat = if state.in_hand().len() == 2 && !state.seats[state.button].folded {
+            state.button
+        } else {
+            state.next_in_hand(state.button)
+        };
+        let big_blind_seat = state.next_in_hand(small_blind_seat);
+        state.post_blind(small_blind_seat, state.config.small_blind, Blind::Small);
+        state.post_blind(big_blind_seat, state.config.big_blind, Blind::Big);
+        // The straddler has to be in the hand with chips left, and can't be in the blinds
+        let straddler = posts
+            .straddle
+            .map(|straddle| match straddle {
+                Straddle::UnderTheGun => state.next_in_hand(big_blind_seat),
+                Straddle::Button => state.button,
+            })
+            .filter(|&seat| !state.seats[seat].folded && state.seats[seat].stack > 0 && seat != small_blind_seat && seat != big_blind_seat);
+        // Players already in the blinds don't post again, and a straddle covers a big blind owed
+        let posting = |seat: usize| seat < num_seats && seat != small_blind_seat && seat != big_blind_seat;
+        for &seat in &posts.live {
+            if posting(seat) && !state.seats[seat].folded && Some(seat) != straddler {
+                state.post_blind(seat, state.config.big_blind, Blind::Big);
+            }
+        }
+        // Dead blinds are like antes, in the pot but not part of the player's bet
+        for &seat in &posts.dead {
+            if posting(seat) && !state.seats[seat].folded {
+                let amount = state.config.small_blind.min(state.seats[seat].stack);
+                state.move_to_pot(seat, amount, ChipMoveKind::Blind);
+                state.emit_post(seat, Blind::Dead, amount);
+            }
+        }
+        if let Some(seat) = straddler {
+            let straddle = 2 * state.config.big_blind;
+            let amount = straddle.min(state.seats[seat].stack);
+            state.move_to_pot(seat, amount, ChipMoveKind::Blind);
+            state.emit_post(seat, Blind::Straddle, amount);
+            state.round.straddle(seat, amount, straddle);
+        }
+
+        state.deal_street()?;
+        state.to_act = state.next_to_act(straddler.unwrap_or(big_blind_seat));
+        state.settle_if_round_over()?;
+        Ok(state)
+    }
+
+    // Starts a hand with a deck shuffled from a seed, the same seed always deals the same cards
+    pub fn with_seed(config: GameConfig, stacks: Vec<u32>, button: usize, seed: u64) -> Result<Self, RuleError> {
+        let deck = config.variant.shuffled_deck(&mut StdRng::seed_from_u64(seed)).map_err(|_| RuleError::NotEnoughCards)?;
+        Ok(GameState::new(config, stacks, button, deck)?.with_shuffle_seed(seed))
+    }
+
+    // A state the observing player can't tell from the real one, for searching and rolling out the hand from their turn
+    // Everything they can see comes from the observation, the cards they can't are made up: hands has the face down cards
+    // of every seat (the observer's own are taken from the observation) and the deck deals the rest
+    pub fn from_observation(observation: &Observation, hands: Vec<Vec<Card>>, deck: Deck) -> Result<Self, RuleError> {
+        let num_seats = observation.stacks.len();
+        if hands.len() != num_seats {
+            return Err(RuleError::WrongHands(num_seats));
+        }
+        let mut ledger = ChipLedger::new((0..num_seats).map(|seat| observation.stacks[seat] + observation.committed[seat]).collect());
+        let seats = hands
+            .into_iter()
+            .enumerate()
+            .map(|(seat, hole)| {
+                ledger.record(ChipMoveKind::Bet, Some(seat), observation.committed[seat]);
+                Seat {
+                    stack: observation.stacks[seat],
+                    committed: observation.committed[seat],
+                    folded: observation.folded[seat],
+                    hole: if seat == observation.seat { observation.hole_cards.clone() } else { hole },
+                    up: observation.up_cards[seat].clone(),
+                }
+            })
+            .collect();
+        // During a draw the seats that haven't drawn on this street are still to, in order from the left of the button
+        let drawn = |seat: usize| observation.history.iter().any(|record| record.street == observation.street && record.seat == seat && matches!(record.action, Action::Draw(_)));
+        let to_draw = if observation.config.variant.streets()[observation.street].draw && !drawn(observation.seat) {
+            (1..=num_seats).map(|offset| (observation.button + offset) % num_seats).filter(|&seat| !observation.folded[seat] && !drawn(seat)).collect()
+        } else {
+            Vec::new()
+        };
+        Ok(GameState {
+            config: observation.config.clone(),
+            seats,
+            button: observation.button,
+            deck,
+            boards: observation.boards.clone(),
+            street: observation.street,
+            round: observation.round.clone(),
+            to_act: Some(observation.seat),
+            history: observation.history.clone(),
+            result: None,
+            to_draw,
+            muck: Vec::new(),
+            rng: StdRng::seed_from_u64(0),
+            max_runouts: 1,
+            awaiting_runouts: false,
+            runout_boards: Vec::new(),
+            ledger,
+            audit: false,
+            events: Vec::new(),
+        })
+    }
+
+    // Same state with the seed used to shuffle the discards back into the deck in draw games
+    pub fn with_shuffle_seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+
+    // Same state where the board may be run up to max_runouts times once everyone is all in
+    // The hand then stops before the rest of the board is dealt, until run_out says how many times to deal it
+    pub fn with_runouts_offered(mut self, max_runouts: usize) -> Self {
+        self.max_runouts = max_runouts.max(1);
+        self
+    }
+
+    // Same state checked against its chip ledger after every action from now on, panicking if any chips were made or lost
+    // Meant for simulations and tests, a mismatch is a bug in the engine
+    pub fn with_audit(mut self) -> Self {
+        self.audit = true;
+        self.audit_chips();
+        self
+    }
+
+    // Every movement of chips in the hand so far
+    pub fn ledger(&self) -> &ChipLedger {
+        &self.ledger
+    }
+
+    // Checks the stacks and the pot against the ledger
+    pub fn check_chips(&self) -> Result<(), LedgerError> {
+        let pot = if self.result.is_some() { 0 } else { self.pot() };
+        self.ledger.audit(&self.stacks(), pot)
+    }
+
+    // In audit mode, panics if the chips don't match the ledger
+    fn audit_chips(&self) {
+        if !self.audit {
+            return;
+        }
+        if let Err(e) = self.check_chips() {
+            panic!("Chips not conserved after {:?}: {}", self.history.last(), e);
+        }
+    }
+
+    // Posts a blind, a player who cannot cover it is all in for what they have
+    fn post_blind(&mut self, seat: usize, blind: u32, kind: Blind) {
+        let amount = blind.min(self.seats[seat].stack);
+        self.move_to_pot(seat, amount, ChipMoveKind::Blind);
+        self.emit_post(seat, kind, amount);
+        self.round.post(seat, amount, blind);
+    }
+
+    // Tells the observers about a forced bet, nothing is posted when there is no ante or blind
+    fn emit_post(&mut self, seat: usize, blind: Blind, amount: u32) {
+        if amount > 0 {
+            self.events.push(GameEvent::BlindPosted { seat, blind, amount });
+        }
+    }
+
+    // Rules of the hand
+    pub fn config(&self) -> &GameConfig {
+        &self.config
+    }
+
+    // Number of seats at the table, including those sitting out
+    pub fn num_seats(&self) -> usize {
+        self.seats.len()
+    }
+
+    // Seat with the dealer button
+    pub fn button(&self) -> usize {
+        self.button
+    }
+
+    // Chips behind for a seat
+    pub fn stack(&self, seat: usize) -> u32 {
+        self.seats[seat].stack
+    }
+
+    // Chips behind for every seat
+    pub fn stacks(&self) -> Vec<u32> {
+        self.seats.iter().map(|seat| seat.stack).collect()
+    }
+
+    // Chips a seat has put in the pot this hand
+    pub fn committed(&self, seat: usize) -> u32 {
+        self.seats[seat].committed
+    }
+
+    // Chips in the middle, including the bets of the current round
+    pub fn pot(&self) -> u32 {
+        self.seats.iter().map(|seat| seat.committed).sum()
+    }
+
+    // The betting round on the current street
+    pub fn round(&self) -> &BettingRound {
+        &self.round
+    }
+
+    // Index of the current street, 0 is the first betting round
+    pub fn street(&self) -> usize {
+        self.street
+    }
+
+    // Community cards dealt so far, the first board in games with more than one
+    pub fn board(&self) -> &[Card] {
+        &self.boards[0]
+    }
+
+    // Every board dealt so far, one for each share of the pot
+    pub fn boards(&self) -> &[Vec<Card>] {
+        &self.boards
+    }
+
+    // Private cards of a seat, only the player in that seat gets to see them
+    pub fn hole_cards(&self, seat: usize) -> &[Card] {
+        &self.seats[seat].hole
+    }
+
+    // Face up cards of a seat, seen by everyone
+    pub fn up_cards(&self, seat: usize) -> &[Card] {
+        &self.seats[seat].up
+    }
+
+    // Cards of a seat as the player in seat viewer sees them, the down cards only for their own seat
+    pub fn cards_seen_by(&self, viewer: usize, seat: usize) -> Vec<Card> {
+        if viewer == seat {
+            [self.seats[seat].hole.as_slice(), &self.seats[seat].up].concat()
+        } else {
+            self.seats[seat].up.clone()
+        }
+    }
+
+    // Every action taken so far
+    pub fn history(&self) -> &[ActionRecord] {
+        &self.history
+    }
+
+    // Everything that happened in the hand so far, including the players' face down cards
+    pub fn events(&self) -> &[GameEvent] {
+        &self.events
+    }
+
+    // Seat that has to act next
+    pub fn to_act(&self) -> Option<usize> {
+        self.to_act
+    }
+
+    // Whether the players are drawing rather than betting
+    pub fn is_drawing(&self) -> bool {
+        !self.to_draw.is_empty()
+    }
+
+    // Whether everyone is all in and the hand is waiting to hear how many times to run the board
+    pub fn is_awaiting_runouts(&self) -> bool {
+        self.awaiting_runouts
+    }
+
+    // Boards dealt when the board was run more than once, empty otherwise
+    pub fn runout_boards(&self) -> &[Vec<Card>] {
+        &self.runout_boards
+    }
+
+    // Whether the hand is over
+    pub fn is_terminal(&self) -> bool {
+        self.result.is_some()
+    }
+
+    // Chips a seat won or lost in the hand once it is over, None until then
+    pub fn utility(&self, seat: usize) -> Option<i64> {
+        let result = self.result.as_ref()?;
+        Some(result.winnings[seat] as i64 - self.seats[seat].committed as i64)
+    }
+
+    // How the hand was settled, None until it is over
+    pub fn result(&self) -> Option<&HandResult> {
+        self.result.as_ref()
+    }
+
+    // Whether a seat has folded (or sat the hand out)
+    pub fn has_folded(&self, seat: usize) -> bool {
+        self.seats[seat].folded
+    }
+
+    // Seats that have not folded
+    pub fn in_hand(&self) -> Vec<usize> {
+        (0..self.seats.len()).filter(|&seat| !self.seats[seat].folded).collect()
+    }
+
+    // Seats that have not folded and still have chips to bet with
+    fn live(&self) -> Vec<usize> {
+        self.in_hand().into_iter().filter(|&seat| self.seats[seat].stack > 0).collect()
+    }
+
+    // The legal actions for the player to act, None when nobody is to act
+    pub fn legal_actions(&self) -> Option<LegalActions> {
+        let seat = self.to_act?;
+        if self.is_drawing() {
+            return Some(LegalActions {
+                seat,
+                can_check: false,
+                call: None,
+                bet: None,
+                raise: None,
+                all_in: None,
+                bring_in: None,
+                draw: Some(self.seats[seat].hole.len()),
+            });
+        }
+        let stack = self.seats[seat].stack;
+        let to_call = self.round.to_call(seat);
+        let all_in = self.round.bet_of(seat) + stack; // Total for the round if every chip goes in
+        let current_bet = self.round.current_bet();
+        let min = self.round.min_raise_to();
+        // The largest bet depends on the structure: all in, the pot after calling, or the fixed bet
+        let max = match self.config.structure {
+            BettingStructure::NoLimit => all_in,
+            BettingStructure::PotLimit => (current_bet + self.pot() + to_call).max(min).min(all_in),
+            BettingStructure::FixedLimit { .. } => min.min(all_in),
+        };
+        let capped = match self.config.structure {
+            BettingStructure::FixedLimit { cap, .. } => self.round.raises() >= cap,
+            _ => false,
+        };
+        let can_raise = self.round.can_raise(seat) && !capped && all_in > current_bet;
+        // A bet or raise has to reach the minimum unless it is all in
+        let range = if can_raise && all_in >= min { Some(BetRange { min, max }) } else { None };
+        // The first player on third street in stud has to bring it in or complete, they can't check or fold
+        let bring_in = self.config.variant.is_stud() && self.street == 0 && self.config.bring_in > 0 && current_bet == 0;
+        Some(LegalActions {
+            seat,
+            can_check: to_call == 0 && !bring_in,
+            call: if to_call > 0 { Some(to_call.min(stack)) } else { None },
+            bet: if current_bet == 0 { range } else { None },
+            raise: if current_bet > 0 { range } else { None },
+            all_in: if (can_raise && all_in <= max) || (to_call > 0 && all_in <= current_bet) { Some(all_in) } else { None },
+            bring_in: if bring_in { Some(self.config.bring_in.min(stack)) } else { None },
+            draw: None,
+        })
+    }
+
+    // Checks an action against the rules without changing anything
+    pub fn validate(&self, action: &Action) -> Result<(), RuleError> {
+        let legal = self.legal_actions().ok_or(RuleError::NoPlayerToAct)?;
+        if legal.contains(action) {
+            return Ok(());
+        }
+        // Work out what was wrong with it so the player can be told
+        if legal.draw.is_some() {
+            return Err(match action {
+                Action::Draw(_) => RuleError::InvalidDiscards,
+                _ => RuleError::MustDraw,
+            });
+        }
+        let current_bet = self.round.current_bet();
+        Err(match *action {
+            Action::Fold => RuleError::MustBringIn(legal.bring_in.unwrap_or_default()),
+            Action::Check => RuleError::CannotCheck(self.round.to_call(legal.seat)),
+            Action::Call => RuleError::NothingToCall,
+            Action::Bet(_) if current_bet > 0 => RuleError::BetFacingBet(current_bet),
+            Action::Raise(_) if current_bet == 0 => RuleError::RaiseWithoutBet,
+            Action::Bet(amount) | Action::Raise(amount) => match legal.bet.or(legal.raise) {
+                None => RuleError::RaiseNotAllowed,
+                Some(range) if amount < range.min => RuleError::BelowMinimum { amount, min: range.min },
+                Some(range) => RuleError::AboveMaximum { amount, max: range.max },
+            },
+            Action::AllIn(amount) => match legal.all_in {
+                None => RuleError::RaiseNotAllowed,
+                Some(total) => RuleError::WrongAllIn { amount, total },
+            },
+            Action::Draw(_) => RuleError::NotDrawing,
+        })
+    }
+
+    // Returns the state after the player to act takes the action, this state is left as it is
+    pub fn apply(&self, action: Action) -> Result<GameState, RuleError> {
+        let mut next = self.clone();
+        next.act(action)?;
+        next.audit_chips();
+        Ok(next)
+    }
+
+    // Returns the state after dealing the rest of the board times times and settling the hand, once everyone agreed to it
+    pub fn run_out(&self, times: usize) -> Result<GameState, RuleError> {
+        if !self.awaiting_runouts {
+            return Err(RuleError::NotAllIn);
+        }
+        if times == 0 || times > self.max_runouts {
+            return Err(RuleError::TooManyRunouts(self.max_runouts));
+        }
+        let mut next = self.clone();
+        next.awaiting_runouts = false;
+        if times == 1 {
+            next.max_runouts = 1; // Decided, so the board is dealt out without asking again
+            next.deal_on()?;
+            next.audit_chips();
+            return Ok(next);
+        }
+        // Every runout starts from the board as it is, the cards keep coming off the same deck
+        let (street, board) = (next.street, next.board().to_vec());
+        for _ in 0..times {
+            next.street = street;
+            next.boards[0] = board.clone();
+            while next.street + 1 < next.config.variant.streets().len() {
+                next.street += 1;
+                next.deal_street()?;
+            }
+            next.runout_boards.push(next.boards[0].clone());
+        }
+        next.boards[0] = next.runout_boards[0].clone();
+        next.award(true);
+        next.audit_chips();
+        Ok(next)
+    }
+
+    // Validates and applies the action of the player to act, moving on to the next street or the showdown when betting is done
+    fn act(&mut self, action: Action) -> Result<(), RuleError> {
+        self.validate(&action)?;
+        let seat = self.to_act.ok_or(RuleError::NoPlayerToAct)?;
+        match action {
+            Action::Fold => self.seats[seat].folded = true,
+            Action::Check => self.round.call(seat, 0),
+            Action::Call => {
+                let amount = self.round.to_call(seat).min(self.seats[seat].stack);
+                self.move_to_pot(seat, amount, ChipMoveKind::Bet);
+                self.round.call(seat, amount);
+            }
+            Action::Bet(total) | Action::Raise(total) | Action::AllIn(total) => {
+                let amount = total - self.round.bet_of(seat);
+                self.move_to_pot(seat, amount, ChipMoveKind::Bet);
+                if total > self.round.current_bet() {
+                    self.round.raise_to(seat, total);
+                } else {
+                    self.round.call(seat, amount); // All in for no more than a call
+                }
+            }
+            Action::Draw(discards) => {
+                self.draw_cards(seat, discards)?;
+                self.record(seat, action);
+                self.to_draw.remove(0);
+                self.to_act = self.to_draw.first().copied();
+                // Once everyone has drawn the betting starts, or the cards are dealt on if nobody can bet
+                if self.to_draw.is_empty() && !self.start_betting() {
+                    return self.deal_on();
+                }
+                return Ok(());
+            }
+        }
+        self.record(seat, action);
+        self.to_act = self.next_to_act(seat);
+        self.settle_if_round_over()
+    }
+
+    // Adds an action to the history
+    fn record(&mut self, seat: usize, action: Action) {
+        self.history.push(ActionRecord { street: self.street, seat, action });
+        self.events.push(GameEvent::ActionTaken { street: self.street, seat, action });
+    }
+
+    // Replaces the discarded cards with new ones from the deck, kept cards stay in order and the new ones go after them
+    // If the deck runs out the cards already thrown away are shuffled to make a new one
+    fn draw_cards(&mut self, seat: usize, discards: Discards) -> Result<(), RuleError> {
+        let hole = std::mem::take(&mut self.seats[seat].hole);
+        let mut thrown = Vec::new();
+        for (position, card) in hole.into_iter().enumerate() {
+            if discards.contains(position) {
+                thrown.push(card);
+            } else {
+                self.seats[seat].hole.push(card);
+            }
+        }
+        let kept = self.seats[seat].hole.len();
+        for _ in 0..thrown.len() {
+            if self.deck.remaining() == 0 {
+                let mut cards = std::mem::take(&mut self.muck);
+                cards.shuffle(&mut self.rng);
+                self.deck = Deck::from_cards(cards);
+            }
+            let card = self.deck.draw().map_err(|_| RuleError::NotEnoughCards)?;
+            self.seats[seat].hole.push(card);
+        }
+        if kept < self.seats[seat].hole.len() {
+            self.events.push(GameEvent::CardsDealt { seat, cards: self.seats[seat].hole[kept..].to_vec(), face_up: false });
+        }
+        self.muck.extend(thrown);
+        Ok(())
+    }
+
+    // Moves chips from a seat's stack into the pot
+    fn move_to_pot(&mut self, seat: usize, amount: u32, kind: ChipMoveKind) {
+        self.ledger.record(kind, Some(seat), amount);
+        self.seats[seat].stack -= amount;
+        self.seats[seat].committed += amount;
+    }
+
+    // The betting round is over once one player is left, everyone able to act has matched the bet,
+    // or a single player with chips has nothing to call from the all in players
+    fn round_over(&self) -> bool {
+        let live = self.live();
+        self.in_hand().len() <= 1
+            || self.round.is_complete(&live)
+            || live.iter().all(|&seat| self.round.to_call(seat) == 0) && live.len() <= 1
+    }
+
+    // Once betting on a street is done: returns any uncalled bet, then deals on or settles the hand
+    fn settle_if_round_over(&mut self) -> Result<(), RuleError> {
+        if !self.round_over() {
+            return Ok(());
+        }
+        self.to_act = None;
+        self.return_uncalled_bet();
+        if self.in_hand().len() <= 1 {
+            self.award(false);
+            return Ok(());
+        }
+        self.deal_on()
+    }
+
+    // Keeps dealing streets until there is a draw or betting to be done again, or the last street has been dealt
+    fn deal_on(&mut self) -> Result<(), RuleError> {
+        loop {
+            if self.street + 1 >= self.config.variant.streets().len() {
+                self.award(true);
+                return Ok(());
+            }
+            if self.can_run_more_than_once() {
+                self.awaiting_runouts = true;
+                return Ok(());
+            }
+            self.street += 1;
+            self.round = BettingRound::new(self.seats.len(), self.config.bet_size(self.street));
+            self.deal_street()?;
+            // Everyone still in the hand draws, all in players too, starting left of the button
+            if self.config.variant.streets()[self.street].draw {
+                let num_seats = self.seats.len();
+                self.to_draw = (1..=num_seats).map(|offset| (self.button + offset) % num_seats).filter(|&seat| !self.seats[seat].folded).collect();
+                self.to_act = self.to_draw.first().copied();
+                return Ok(());
+            }
+            if self.start_betting() {
+                return Ok(());
+            }
+        }
+    }
+
+    // The board can be run more than once when it is offered, nobody can bet any more and only board cards are left to deal
+    // Games with more than one board already split the pot and are always run once
+    fn can_run_more_than_once(&self) -> bool {
+        self.max_runouts > 1
+            && self.boards.len() == 1
+            && self.runout_boards.is_empty()
+            && self.live().len() < 2
+            && self.in_hand().len() >= 2
+            && self.config.variant.streets()[self.street + 1..].iter().all(|street| street.down == 0 && street.up == 0 && !street.draw)
+    }
+
+    // Gives the first player the action if at least two players can still bet
+    fn start_betting(&mut self) -> bool {
+        if self.live().len() < 2 {
+            return false;
+        }
+        self.to_act = self.first_to_act();
+        true
+    }
+
+    // If the biggest bet was not matched by anyone the difference goes back to the player who made it
+    fn return_uncalled_bet(&mut self) {
+        let mut committed: Vec<(u32, usize)> = self.seats.iter().enumerate().map(|(seat, s)| (s.committed, seat)).collect();
+        committed.sort_unstable_by(|a, b| b.cmp(a));
+        let (top, seat) = committed[0];
+        let second = committed.get(1).map_or(0, |c| c.0);
+        if top > second {
+            self.seats[seat].committed -= top - second;
+            self.seats[seat].stack += top - second;
+            self.ledger.record(ChipMoveKind::UncalledBet, Some(seat), top - second);
+        }
+    }
+
+    // Deals the cards for the current street, players' cards one at a time starting left of the button, then each board in turn
+    // On the last street of stud, if the deck can't give every player a card, one card is dealt face up in the middle
+    // for everyone to use instead, as with eight players; any other game running out of cards is an error
+    fn deal_street(&mut self) -> Result<(), RuleError> {
+        let streets = self.config.variant.streets();
+        let street = streets[self.street];
+        let shared_river = self.config.variant.is_stud() && self.street == streets.len() - 1;
+        let order: Vec<usize> = (1..=self.seats.len())
+            .map(|offset| (self.button + offset) % self.seats.len())
+            .filter(|&seat| !self.seats[seat].folded)
+            .collect();
+        // Cards each player had before, so the new ones can be told to the observers
+        let before: Vec<(usize, usize)> = self.seats.iter().map(|seat| (seat.hole.len(), seat.up.len())).collect();
+        let boards_before: Vec<usize> = self.boards.iter().map(|board| board.len()).collect();
+        let rounds = std::iter::repeat_n(false, street.down).chain(std::iter::repeat_n(true, street.up));
+        for face_up in rounds {
+            if shared_river && self.deck.remaining() < order.len() {
+                let card = self.deck.draw().map_err(|_| RuleError::NotEnoughCards)?;
+                self.boards[0].push(card);
+                continue;
+            }
+            for &seat in &order {
+                let card = self.deck.draw().map_err(|_| RuleError::NotEnoughCards)?;
+                if face_up {
+                    self.seats[seat].up.push(card);
+                } else {
+                    self.seats[seat].hole.push(card);
+                }
+            }
+        }
+        for board in 0..self.boards.len() {
+            for _ in 0..street.board {
+                let card = self.deck.draw().map_err(|_| RuleError::NotEnoughCards)?;
+                self.boards[board].push(card);
+            }
+        }
+        for &seat in &order {
+            let (hole, up) = before[seat];
+            if hole < self.seats[seat].hole.len() {
+                self.events.push(GameEvent::CardsDealt { seat, cards: self.seats[seat].hole[hole..].to_vec(), face_up: false });
+            }
+            if up < self.seats[seat].up.len() {
+                self.events.push(GameEvent::CardsDealt { seat, cards: self.seats[seat].up[up..].to_vec(), face_up: true });
+            }
+        }
+        for (board, before) in boards_before.into_iter().enumerate() {
+            if before < self.boards[board].len() {
+                self.events.push(GameEvent::StreetDealt {
+                    street: self.street,
+                    run: board + self.runout_boards.len(),
+                    cards: self.boards[board][before..].to_vec(),
+                    board: self.boards[board].clone(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    // Splits the pot into main and side pots and pays out the winners, at a showdown the best hand wins each pot
+    // In hi/lo games the best qualifying low takes half of each pot, and every board, or a board run several times,
+    // plays for an equal share of each pot
+    fn award(&mut self, showdown: bool) {
+        let committed: Vec<u32> = self.seats.iter().map(|seat| seat.committed).collect();
+        let folded: Vec<bool> = self.seats.iter().map(|seat| seat.folded).collect();
+        let mut pots = build_pots(&committed, &folded);
+        // Rake comes out of the main pot first, then the side pots
+        let dealt = self.seats.iter().filter(|seat| !seat.hole.is_empty() || !seat.up.is_empty()).count();
+        let flop = self.config.variant.flop_street().is_some_and(|street| self.street >= street);
+        let rake = self.config.rake.as_ref().map_or(0, |rake| rake.amount(committed.iter().sum(), dealt, flop));
+        let mut to_take = rake;
+        for pot in pots.iter_mut() {
+            let taken = to_take.min(pot.amount);
+            pot.amount -= taken;
+            to_take -= taken;
+        }
+        self.ledger.record(ChipMoveKind::Rake, None, rake);
+        if rake > 0 {
+            self.events.push(GameEvent::RakeTaken { amount: rake });
+        }
+
+        let boards = if self.runout_boards.is_empty() { self.boards.clone() } else { self.runout_boards.clone() };
+        let times = boards.len();
+        let mut winnings = vec![0; self.seats.len()];
+        let mut runouts = Vec::with_capacity(times);
+        for (run, board) in boards.into_iter().enumerate() {
+            let (hands, lows) = self.shown_hands(showdown, &board);
+            for seat in (0..self.seats.len()).filter(|&seat| showdown && !self.seats[seat].folded) {
+                let cards = [self.seats[seat].hole.as_slice(), &self.seats[seat].up].concat();
+                self.events.push(GameEvent::Showdown { run, seat, cards, hand: hands[seat], low: lows[seat] });
+            }
+            let mut shares: Vec<Pot> = pots.iter().map(|pot| pot.fraction(run, times)).collect();
+            for (index, pot) in shares.iter_mut().enumerate() {
+                let best = pot.eligible.iter().filter_map(|&seat| hands[seat]).max();
+                // Lowball games have no high hands at the showdown, the low takes the whole pot
+                if !(showdown && best.is_none()) {
+                    pot.winners = pot.eligible.iter().copied().filter(|&seat| hands[seat] == best).collect();
+                }
+                if let Some(best_low) = pot.eligible.iter().filter_map(|&seat| lows[seat]).max() {
+                    pot.low_winners = pot.eligible.iter().copied().filter(|&seat| lows[seat] == Some(best_low)).collect();
+                }
+                // A seat that scoops a hi/lo pot gets both halves, tell the observers about them together
+                let mut awarded: Vec<(usize, u32)> = Vec::new();
+                for (seat, share) in pot.split_hi_lo(self.button, self.seats.len()) {
+                    winnings[seat] += share;
+                    match awarded.iter_mut().find(|(winner, _)| *winner == seat) {
+                        Some((_, amount)) => *amount += share,
+                        None => awarded.push((seat, share)),
+                    }
+                }
+                for (seat, amount) in awarded.into_iter().filter(|&(_, amount)| amount > 0) {
+                    let hand = hands[seat].filter(|_| pot.winners.contains(&seat));
+                    let low = lows[seat].filter(|_| pot.low_winners.contains(&seat));
+                    self.events.push(GameEvent::PotAwarded { run, pot: index, seat, amount, hand, low });
+                }
+            }
+            runouts.push(Runout { board, pots: shares, hands, lows });
+        }
+        for (seat, &won) in winnings.iter().enumerate() {
+            self.seats[seat].stack += won;
+            self.ledger.record(ChipMoveKind::Award, Some(seat), won);
+        }
+        self.result = Some(if times == 1 {
+            let Runout { pots, hands, lows, .. } = runouts.remove(0);
+            HandResult { pots, winnings, hands, lows, rake, runouts: Vec::new() }
+        } else {
+            let num_seats = self.seats.len();
+            HandResult { pots, winnings, hands: vec![None; num_seats], lows: vec![None; num_seats], rake, runouts }
+        });
+    }
+
+    // Best hand and qualifying low of every seat shown down with the given board, None for seats that folded or without a showdown
+    fn shown_hands(&self, showdown: bool, board: &[Card]) -> (Vec<Option<Hand>>, Vec<Option<LowHand>>) {
+        let variant = self.config.variant;
+        self.seats
+            .iter()
+            .map(|seat| {
+                if !showdown || seat.folded {
+                    return (None, None);
+                }
+                let cards = [seat.hole.as_slice(), &seat.up].concat();
+                (variant.best_hand(&cards, board), variant.best_low(&cards, board))
+            })
+            .unzip()
+    }
+
+    // Seat that opens the betting on the current street
+    // Flop games start left of the button, stud starts with the lowest card showing on third street
+    // and after that with the best hand showing (ties go to the seat closest to the left of the button)
+    // Razz turns it around: the highest door card (aces are low) brings it in and the lowest hand showing acts first
+    fn first_to_act(&self) -> Option<usize> {
+        if !self.config.variant.is_stud() {
+            return self.next_to_act(self.button);
+        }
+        let num_seats = self.seats.len();
+        let order = (1..=num_seats).map(|offset| (self.button + offset) % num_seats);
+        let first = if self.config.variant.is_lowball() && self.street == 0 {
+            // Highest door card by rank and then by suit, spades being the highest suit
+            order
+                .filter(|&seat| !self.seats[seat].folded && self.seats[seat].stack > 0)
+                .filter_map(|seat| self.seats[seat].up.first().map(|card| (card.rank() % 14, suit_order(card.suit()), seat)))
+                .max()
+                .map(|(_, _, seat)| seat)?
+        } else if self.config.variant.is_lowball() {
+            // min_by_key keeps the first of equal elements
+            order.filter(|&seat| !self.seats[seat].folded).min_by_key(|&seat| showing_low_strength(&self.seats[seat].up))?
+        } else if self.street == 0 {
+            // Lowest door card by rank and then by suit, clubs being the lowest suit
+            order
+                .filter(|&seat| !self.seats[seat].folded && self.seats[seat].stack > 0)
+                .filter_map(|seat| self.seats[seat].up.first().map(|card| (card.rank(), suit_order(card.suit()), seat)))
+                .min()
+                .map(|(_, _, seat)| seat)?
+        } else {
+            // max_by_key keeps the last of equal elements, so look through the seats in reverse
+            order
+                .filter(|&seat| !self.seats[seat].folded)
+                .collect::<Vec<usize>>()
+                .into_iter()
+                .rev()
+                .max_by_key(|&seat| showing_strength(&self.seats[seat].up))?
+        };
+        // A player who is all in can't open, the next player with chips does
+        if self.seats[first].stack > 0 {
+            Some(first)
+        } else {
+            self.next_to_act(first)
+        }
+    }
+
+    // Next seat after the given one that has not folded and still has chips
+    fn next_to_act(&self, seat: usize) -> Option<usize> {
+        let num_seats = self.seats.len();
+        (1..=num_seats)
+            .map(|offset| (seat + offset) % num_seats)
+            .find(|&next| !self.seats[next].folded && self.seats[next].stack > 0)
+    }
+
+    // Next seat after the given one that is in the hand, whether or not it has chips left
+    fn next_in_hand(&self, seat: usize) -> usize {
+        let num_seats = self.seats.len();
+        (1..=num_seats)
+            .map(|offset| (seat + offset) % num_seats)
+            .find(|&next| !self.seats[next].folded)
+            .unwrap_or(seat)
+    }
+}
+
+// Order of the suits for deciding the bring-in, from lowest to highest
+fn suit_order(suit: Suit) -> u8 {
+    match suit {
+        Suit::Clubs => 0,
+        Suit::Diamonds => 1,
+        Suit::Hearts => 2,
+        Suit::Spades => 3,
+    }
+}
+
+// Errors for actions that break the rules
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+pub enum RuleError {
+    #[error("There is no player to act")]
+    NoPlayerToAct,
+    #[error("Cannot check, {0} to call")]
+    CannotCheck(u32),
+    #[error("There is nothing to call")]
+    NothingToCall,
+    #[error("There is already a bet of {0}, raise instead")]
+    BetFacingBet(u32),
+    #[error("Players are drawing, discard some cards or stand pat")]
+    MustDraw,
+    #[error("There is no draw now")]
+    NotDrawing,
+    #[error("Can only discard cards in your hand")]
+    InvalidDiscards,
+    #[error("Cannot fold, bring it in for {0} or complete the bet")]
+    MustBringIn(u32),
+    #[error("There is no bet to raise, bet instead")]
+    RaiseWithoutBet,
+    #[error("Betting is not open to this player, call or fold")]
+    RaiseNotAllowed,
+    #[error("A bet or raise to {amount} is below the minimum of {min}")]
+    BelowMinimum { amount: u32, min: u32 },
+    #[error("A bet or raise to {amount} is above the maximum of {max}")]
+    AboveMaximum { amount: u32, max: u32 },
+    #[error("All in is {total}, not {amount}")]
+    WrongAllIn { amount: u32, total: u32 },
+    #[error("A hand needs at least two players with chips")]
+    NotEnoughPlayers,
+    #[error("The board can only be run again once everyone is all in")]
+    NotAllIn,
+    #[error("The board can be run at most {0} times")]
+    TooManyRunouts(usize),
+    #[error("At most {0} players can be dealt in")]
+    TooManyPlayers(usize),
+    #[error("Not enough cards in the deck")]
+    NotEnoughCards,
+    #[error("Expected a hand for each of the {0} seats")]
+    WrongHands(usize),
+    #[error("{0} has no board for a bomb pot or more than one board")]
+    NoBoard(String),
+    #[error("{0} can't be played yet")]
+    UnsupportedVariant(String),
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::card::parse_cards;
+    use crate::game::rake::Rake;
+    use crate::game::variant::PokerVariant;
+    use crate::rank::handrank::HandRank;
+
+    // Hold'em with blinds 1/2, seat 0 is the small blind and seat 1 the big blind
+    fn holdem(stacks: Vec<u32>) -> GameState {
+        let button = if stacks.len() == 2 { 0 } else { stacks.len() - 1 };
+        GameState::with_seed(GameConfig::new(PokerVariant::TexasHoldem, 1, 2), stacks, button, 7).unwrap()
+    }
+
+    // Plays a list of actions, panicking on an illegal one or if any chips go missing
+    fn play(state: GameState, actions: &[Action]) -> GameState {
+        actions.iter().fold(state.with_audit(), |state, &action| state.apply(action).unwrap())
+    }
+
+    #[test]
+    fn test_legal_actions_preflop() {
+        // Blinds 1/2, seat 2 is first to act facing the big blind
+        let state = holdem(vec![100, 100, 100]);
+        let legal = state.legal_actions().unwrap();
+        assert_eq!(legal.seat, 2);
+        assert!(!legal.can_check);
+        assert_eq!(legal.call, Some(2));
+        assert_eq!(legal.bet, None);
+        assert_eq!(legal.raise, Some(BetRange { min: 4, max: 100 }));
+        assert_eq!(legal.all_in, Some(100));
+        assert_eq!(legal.actions(), vec![Action::Fold, Action::Call, Action::Raise(4), Action::AllIn(100)]);
+    }
+    #[test]
+    fn test_big_blind_option() {
+        let state = play(holdem(vec![100, 100, 100]), &[Action::Call, Action::Call]);
+        let legal = state.legal_actions().unwrap();
+        assert_eq!(legal.seat, 1);
+        assert!(legal.can_check);
+        assert_eq!(legal.call, None);
+        assert_eq!(legal.raise, Some(BetRange { min: 4, max: 100 }));
+        let state = state.apply(Action::Check).unwrap();
+        assert_eq!(state.street(), 1);
+        assert_eq!(state.board().len(), 3);
+        assert_eq!(state.pot(), 6);
+    }
+    #[test]
+    fn test_rejects_illegal_actions() {
+        let state = holdem(vec![100, 100, 100]);
+        assert_eq!(state.validate(&Action::Check), Err(RuleError::CannotCheck(2)));
+        assert_eq!(state.validate(&Action::Bet(10)), Err(RuleError::BetFacingBet(2)));
+        assert_eq!(state.validate(&Action::Raise(3)), Err(RuleError::BelowMinimum { amount: 3, min: 4 }));
+        assert_eq!(state.validate(&Action::Raise(101)), Err(RuleError::AboveMaximum { amount: 101, max: 100 }));
+        assert_eq!(state.validate(&Action::AllIn(50)), Err(RuleError::WrongAllIn { amount: 50, total: 100 }));
+        assert_eq!(state.validate(&Action::Raise(4)), Ok(()));
+        assert!(state.apply(Action::Check).is_err());
+    }
+    #[test]
+    fn test_short_stack_can_only_call_all_in() {
+        // Seat 2 has less than the call
+        let state = holdem(vec![100, 100, 1]);
+        let legal = state.legal_actions().unwrap();
+        assert_eq!(legal.call, Some(1));
+        assert_eq!(legal.raise, None);
+        assert_eq!(legal.all_in, Some(1));
+    }
+    #[test]
+    fn test_short_all_in_closes_betting() {
+        // Seat 2 raises to 10, seat 0 is all in for 14 (short), seat 2 can only call or fold the extra 4
+        let state = play(holdem(vec![14, 100, 100]), &[Action::Raise(10), Action::AllIn(14), Action::Call]);
+        let legal = state.legal_actions().unwrap();
+        assert_eq!(legal.seat, 2);
+        assert_eq!(legal.call, Some(4));
+        assert_eq!(legal.raise, None);
+        assert_eq!(legal.all_in, None);
+        assert_eq!(state.validate(&Action::Raise(30)), Err(RuleError::RaiseNotAllowed));
+    }
+    #[test]
+    fn test_opening_bet_range() {
+        // Heads up the big blind acts first after the flop
+        let state = play(holdem(vec![100, 100]), &[Action::Call, Action::Check]);
+        let legal = state.legal_actions().unwrap();
+        assert_eq!(legal.seat, 1);
+        assert!(legal.can_check);
+        assert_eq!(legal.bet, Some(BetRange { min: 2, max: 98 }));
+        assert_eq!(legal.raise, None);
+        assert_eq!(state.validate(&Action::Raise(10)), Err(RuleError::RaiseWithoutBet));
+        assert_eq!(state.validate(&Action::Call), Err(RuleError::NothingToCall));
+        let state = state.apply(Action::Bet(10)).unwrap();
+        assert_eq!(state.legal_actions().unwrap().raise, Some(BetRange { min: 20, max: 98 }));
+    }
+    #[test]
+    fn test_apply_leaves_state_untouched() {
+        let state = holdem(vec![100, 100, 100]);
+        let next = state.apply(Action::Raise(10)).unwrap();
+        assert_eq!(state.stack(2), 100);
+        assert_eq!(state.to_act(), Some(2));
+        assert_eq!(next.stack(2), 90);
+        assert_eq!(next.to_act(), Some(0));
+    }
+    #[test]
+    fn test_everyone_folds_to_big_blind() {
+        let state = play(holdem(vec![100, 100, 100]), &[Action::Fold, Action::Fold]);
+        assert!(state.is_terminal());
+        assert_eq!(state.stacks(), vec![99, 101, 100]);
+        assert_eq!(state.result().unwrap().winnings, vec![0, 2, 0]); // The 1 the small blind did not call goes back first
+        assert!(state.apply(Action::Check).is_err());
+    }
+    #[test]
+    fn test_uncalled_bet_is_returned() {
+        // Seat 2 shoves 100, everyone folds, only the blinds are won
+        let state = play(holdem(vec![100, 100, 100]), &[Action::AllIn(100), Action::Fold, Action::Fold]);
+        assert_eq!(state.stacks(), vec![99, 98, 103]);
+    }
+    #[test]
+    fn test_full_hand_to_showdown() {
+        // Seat 1 gets the first card (left of the button), so seat 1 has aces and seat 0 kings
+        let deck = Deck::stacked("As Kd Ah Kc 2s 7d 9c Jh 3c");
+        let config = GameConfig::new(PokerVariant::TexasHoldem, 1, 2);
+        let state = GameState::new(config, vec![100, 100], 0, deck).unwrap();
+        assert_eq!(state.hole_cards(0), parse_cards("Kd Kc").unwrap().as_slice());
+        let state = play(state, &[Action::Raise(6), Action::Call, Action::Bet(10), Action::Call, Action::Check, Action::Check, Action::Check, Action::Check]);
+        assert!(state.is_terminal());
+        assert_eq!(state.board(), parse_cards("2s 7d 9c Jh 3c").unwrap().as_slice());
+        let result = state.result().unwrap();
+        assert_eq!(result.hands[1].unwrap().rank(), HandRank::OnePair);
+        assert_eq!(result.winnings, vec![0, 32]);
+        assert_eq!(state.stacks(), vec![84, 116]);
+    }
+    #[test]
+    fn test_events_of_a_hand_won_without_a_showdown() {
+        let deck = Deck::stacked("As Kd Ah Kc 2s 7d 9c Jh 3c");
+        let config = GameConfig::new(PokerVariant::TexasHoldem, 1, 2);
+        let state = play(GameState::new(config, vec![100, 100], 0, deck).unwrap(), &[Action::Raise(6), Action::Fold]);
+        assert_eq!(
+            state.events(),
+            &[
+                GameEvent::HandStarted { variant: PokerVariant::TexasHoldem, button: 0, stacks: vec![100, 100] },
+                GameEvent::BlindPosted { seat: 0, blind: Blind::Small, amount: 1 },
+                GameEvent::BlindPosted { seat: 1, blind: Blind::Big, amount: 2 },
+                GameEvent::CardsDealt { seat: 1, cards: parse_cards("As Ah").unwrap(), face_up: false },
+                GameEvent::CardsDealt { seat: 0, cards: parse_cards("Kd Kc").unwrap(), face_up: false },
+                GameEvent::ActionTaken { street: 0, seat: 0, action: Action::Raise(6) },
+                GameEvent::ActionTaken { street: 0, seat: 1, action: Action::Fold },
+                GameEvent::PotAwarded { run: 0, pot: 0, seat: 0, amount: 4, hand: None, low: None },
+            ]
+        );
+    }
+    #[test]
+    fn test_events_of_a_showdown() {
+        let deck = Deck::stacked("As Kd Ah Kc 2s 7d 9c Jh 3c");
+        let config = GameConfig::new(PokerVariant::TexasHoldem, 1, 2);
+        let state = play(GameState::new(config, vec![100, 100], 0, deck).unwrap(), &[Action::Call, Action::Check]);
+        let state = play(state, &[Action::Check; 6]);
+        let boards: Vec<usize> = state.events().iter().filter_map(|event| match event {
+            GameEvent::StreetDealt { board, .. } => Some(board.len()),
+            _ => None,
+        }).collect();
+        assert_eq!(boards, vec![3, 4, 5]);
+        let shown = state.events().iter().filter(|event| matches!(event, GameEvent::Showdown { .. })).count();
+        assert_eq!(shown, 2);
+        let aces = state.result().unwrap().hands[1];
+        assert_eq!(state.events().last(), Some(&GameEvent::PotAwarded { run: 0, pot: 0, seat: 1, amount: 4, hand: aces, low: None }));
+    }
+    #[test]
+    fn test_all_in_runs_out_the_board_with_side_pot() {
+        // Seat 0 is all in for 20 and wins the main pot, seat 1 wins the side pot against seat 2
+        // Cards go left of the button (seat 2) so seat 0 gets the first card
+        let deck = Deck::stacked("As Kd Qc Ah Kc Qh 2s 7d 9c Jh 3c");
+        let config = GameConfig::new(PokerVariant::TexasHoldem, 1, 2);
+        let state = GameState::new(config, vec![20, 100, 100], 2, deck).unwrap();
+        let state = play(state, &[Action::Raise(50), Action::AllIn(20), Action::Call]);
+        // Seats 1 and 2 still have chips so they keep betting
+        assert_eq!(state.street(), 1);
+        let state = play(state, &[Action::Check, Action::Check, Action::Check, Action::Check, Action::Check, Action::Check]);
+        let result = state.result().unwrap();
+        assert_eq!(result.pots.len(), 2);
+        assert_eq!((result.pots[0].amount, result.pots[0].winners.clone()), (60, vec![0]));
+        assert_eq!((result.pots[1].amount, result.pots[1].winners.clone()), (60, vec![1]));
+        assert_eq!(state.stacks(), vec![60, 110, 50]);
+        assert_eq!(state.stacks().iter().sum::<u32>(), 220);
+    }
+    #[test]
+    fn test_all_in_preflop_deals_to_the_river() {
+        let state = play(holdem(vec![100, 100]), &[Action::AllIn(100), Action::Call]);
+        assert!(state.is_terminal());
+        assert_eq!(state.board().len(), 5);
+        assert_eq!(state.stacks().iter().sum::<u32>(), 200);
+    }
+    #[test]
+    fn test_sitting_out_and_antes() {
+        // Seat 1 has no chips so seat 2 posts the small blind and seat 3 the big blind, everyone antes 1
+        let config = GameConfig::new(PokerVariant::TexasHoldem, 1, 2).with_ante(1);
+        let state = GameState::with_seed(config, vec![100, 0, 100, 100], 1, 3).unwrap();
+        assert!(state.has_folded(1));
+        assert!(state.hole_cards(1).is_empty());
+        assert_eq!(state.stacks(), vec![99, 0, 98, 97]);
+        assert_eq!(state.pot(), 6);
+        assert_eq!(state.to_act(), Some(0));
+    }
+    #[test]
+    fn test_posted_big_blind_is_live() {
+        // Seat 3 joined and posts a big blind out of turn, the big blind in seat 1 doesn't post twice
+        let config = GameConfig::new(PokerVariant::TexasHoldem, 1, 2);
+        let deck = config.variant.shuffled_deck(&mut StdRng::seed_from_u64(5)).unwrap();
+        let state = GameState::with_posts(config, vec![100; 5], 4, deck, &Posts::live(vec![3, 1])).unwrap();
+        assert_eq!(state.stacks(), vec![99, 98, 100, 98, 100]);
+        assert_eq!(state.to_act(), Some(2));
+        // Seat 3 has posted but not acted, after the calls it can check its option
+        let state = play(state, &[Action::Call]);
+        let legal = state.legal_actions().unwrap();
+        assert_eq!(legal.seat, 3);
+        assert!(legal.can_check);
+    }
+    #[test]
+    fn test_under_the_gun_straddle() {
+        // Seat 2 straddles to 4, seat 3 acts first and the minimum raise is to 8
+        let config = GameConfig::new(PokerVariant::TexasHoldem, 1, 2);
+        let deck = config.variant.shuffled_deck(&mut StdRng::seed_from_u64(5)).unwrap();
+        let state = GameState::with_posts(config, vec![100; 4], 3, deck, &Posts::none().with_straddle(Straddle::UnderTheGun)).unwrap();
+        assert_eq!(state.stacks(), vec![99, 98, 96, 100]);
+        let legal = state.legal_actions().unwrap();
+        assert_eq!(legal.seat, 3);
+        assert_eq!(legal.call, Some(4));
+        assert_eq!(legal.raise, Some(BetRange { min: 8, max: 100 }));
+        // Everyone calls round to the straddler, who gets the option
+        let state = play(state, &[Action::Call, Action::Call, Action::Call]);
+        let legal = state.legal_actions().unwrap();
+        assert_eq!(legal.seat, 2);
+        assert!(legal.can_check);
+    }
+    #[test]
+    fn test_button_straddle_small_blind_acts_first() {
+        let config = GameConfig::new(PokerVariant::TexasHoldem, 1, 2);
+        let deck = config.variant.shuffled_deck(&mut StdRng::seed_from_u64(5)).unwrap();
+        let state = GameState::with_posts(config.clone(), vec![100; 4], 3, deck.clone(), &Posts::none().with_straddle(Straddle::Button)).unwrap();
+        assert_eq!(state.stacks(), vec![99, 98, 100, 96]);
+        assert_eq!(state.to_act(), Some(0));
+        let state = play(state, &[Action::Call, Action::Call, Action::Call]);
+        assert_eq!(state.to_act(), Some(3));
+        // Heads up the button is the small blind and can't straddle
+        let state = GameState::with_posts(config, vec![100; 2], 0, deck, &Posts::none().with_straddle(Straddle::Button)).unwrap();
+        assert_eq!(state.stacks(), vec![99, 98]);
+        assert_eq!(state.to_act(), Some(0));
+    }
+    #[test]
+    fn test_dead_small_blind_is_not_a_bet() {
+        // Seat 3 comes back owing both blinds: the big blind is live and the small blind goes in the pot
+        let config = GameConfig::new(PokerVariant::TexasHoldem, 1, 2);
+        let deck = config.variant.shuffled_deck(&mut StdRng::seed_from_u64(5)).unwrap();
+        let state = GameState::with_posts(config, vec![100; 5], 0, deck, &Posts::live(vec![3]).with_dead(vec![3])).unwrap();
+        assert_eq!(state.stacks(), vec![100, 99, 98, 97, 100]);
+        assert_eq!(state.pot(), 6);
+        assert_eq!(state.round().bet_of(3), 2);
+        assert_eq!(state.round().current_bet(), 2);
+    }
+    #[test]
+    fn test_rake_is_taken_before_the_pot_is_paid() {
+        let config = GameConfig::new(PokerVariant::TexasHoldem, 1, 2).with_rake(Rake::new(10).with_cap(5).with_no_flop_no_drop());
+        // Folded round to the big blind, no flop no drop
+        let state = play(GameState::with_seed(config.clone(), vec![100; 3], 2, 7).unwrap(), &[Action::Fold, Action::Fold]);
+        assert_eq!(state.result().unwrap().rake, 0);
+        assert_eq!(state.stacks(), vec![99, 101, 100]);
+        // Called to the river, 10% of the 30 chip pot
+        let state = GameState::with_seed(config, vec![100; 3], 2, 7).unwrap();
+        let state = play(state, &[Action::Raise(10), Action::Call, Action::Call]);
+        let state = play(state, &[Action::Check; 9]);
+        let result = state.result().unwrap();
+        assert_eq!(result.rake, 3);
+        assert_eq!(result.pots[0].amount, 27);
+        assert_eq!(result.winnings.iter().sum::<u32>(), 27);
+        assert_eq!(state.stacks().iter().sum::<u32>(), 297);
+    }
+    #[test]
+    fn test_stud_no_flop_no_drop() {
+        let config = GameConfig::stud(PokerVariant::SevenCardStud, 1, 2, 10, 20).with_rake(Rake::new(10).with_no_flop_no_drop());
+        let cards = "Ah Kh Qh As Ks Qs 9c 2d 2c 3h 4h Jd";
+        // Won on third street when everyone folds to the completion, nothing is taken
+        let state = play(GameState::new(config.clone(), vec![100; 3], 2, Deck::stacked(cards)).unwrap(), &[Action::Bet(2), Action::Raise(10), Action::Fold, Action::Fold]);
+        assert_eq!(state.result().unwrap().rake, 0);
+        // Once fourth street is dealt the pot is raked, 10% of 33 even though it is won there without a showdown
+        let state = play(GameState::new(config, vec![100; 3], 2, Deck::stacked(cards)).unwrap(), &[Action::Bet(2), Action::Raise(10), Action::Call, Action::Call]);
+        assert_eq!(state.street(), 1);
+        let state = play(state, &[Action::Bet(10), Action::Fold, Action::Fold]);
+        assert_eq!(state.result().unwrap().rake, 3);
+    }
+    #[test]
+    fn test_run_it_twice() {
+        // Aces against kings all in before the flop, the aces hold on the first board and the kings hit a set on the second
+        let deck = Deck::stacked("As Kd Ah Kh 2c 7d 9s Jc 3h Ks 2d 4c 8h 5s");
+        let config = GameConfig::new(PokerVariant::TexasHoldem, 1, 2);
+        let state = GameState::new(config, vec![100, 100], 0, deck).unwrap().with_runouts_offered(2);
+        let state = play(state, &[Action::AllIn(100), Action::Call]);
+        assert!(state.is_awaiting_runouts());
+        assert_eq!(state.legal_actions(), None);
+        assert_eq!(state.run_out(3).unwrap_err(), RuleError::TooManyRunouts(2));
+        let state = state.run_out(2).unwrap();
+        let result = state.result().unwrap();
+        assert_eq!(state.runout_boards(), &[parse_cards("2c 7d 9s Jc 3h").unwrap(), parse_cards("Ks 2d 4c 8h 5s").unwrap()]);
+        assert_eq!(result.runouts.len(), 2);
+        assert_eq!((result.runouts[0].pots[0].amount, result.runouts[0].pots[0].winners.clone()), (100, vec![1]));
+        assert_eq!((result.runouts[1].pots[0].amount, result.runouts[1].pots[0].winners.clone()), (100, vec![0]));
+        assert_eq!(result.pots[0].amount, 200);
+        assert_eq!(state.stacks(), vec![100, 100]);
+    }
+    #[test]
+    fn test_run_it_once_when_not_agreed() {
+        let deck = Deck::stacked("As Kd Ah Kh 2c 7d 9s Jc 3h Ks 2d 4c 8h 5s");
+        let config = GameConfig::new(PokerVariant::TexasHoldem, 1, 2);
+        let state = GameState::new(config, vec![100, 100], 0, deck).unwrap().with_runouts_offered(2);
+        let state = play(state, &[Action::AllIn(100), Action::Call]).run_out(1).unwrap();
+        assert!(state.result().unwrap().runouts.is_empty());
+        assert_eq!(state.board(), parse_cards("2c 7d 9s Jc 3h").unwrap().as_slice());
+        assert_eq!(state.stacks(), vec![0, 200]);
+        assert_eq!(state.run_out(1).unwrap_err(), RuleError::NotAllIn);
+    }
+    #[test]
+    fn test_ledger_records_every_chip_movement() {
+        let config = GameConfig::new(PokerVariant::TexasHoldem, 1, 2).with_ante(1).with_rake(Rake::new(10));
+        let state = GameState::with_seed(config, vec![100, 50, 100], 2, 7).unwrap();
+        // Seat 1 is all in for less than the raise, the rest of seat 2's raise comes back to it
+        let state = play(state, &[Action::Raise(80), Action::Fold, Action::AllIn(49)]);
+        let moves: Vec<(ChipMoveKind, Option<usize>, u32)> = state.ledger().moves().iter().map(|m| (m.kind, m.seat, m.amount)).collect();
+        assert_eq!(&moves[..5], &[
+            (ChipMoveKind::Ante, Some(0), 1),
+            (ChipMoveKind::Ante, Some(1), 1),
+            (ChipMoveKind::Ante, Some(2), 1),
+            (ChipMoveKind::Blind, Some(0), 1),
+            (ChipMoveKind::Blind, Some(1), 2),
+        ]);
+        assert_eq!(moves[5], (ChipMoveKind::Bet, Some(2), 80));
+        assert_eq!(moves[6], (ChipMoveKind::Bet, Some(1), 47));
+        assert_eq!(moves[7], (ChipMoveKind::UncalledBet, Some(2), 31));
+        assert_eq!(moves[8], (ChipMoveKind::Rake, None, 10));
+        assert!(moves[9..].iter().all(|m| m.0 == ChipMoveKind::Award));
+        assert_eq!(state.ledger().rake(), 10);
+        assert_eq!(state.check_chips(), Ok(()));
+        assert_eq!(state.stacks().iter().sum::<u32>() + 10, 250);
+    }
+    #[test]
+    #[should_panic(expected = "Chips not conserved")]
+    fn test_audit_catches_chips_made_from_nothing() {
+        let mut state = holdem(vec![100, 100, 100]).with_audit();
+        state.seats[0].stack += 5;
+        let _ = state.apply(Action::Call);
+    }
+    #[test]
+    fn test_split_pot() {
+        // Board plays for both players
+        let deck = Deck::stacked("2s 3d 2h 3c As Ks Qs Js Ts");
+        let state = GameState::new(GameConfig::new(PokerVariant::TexasHoldem, 1, 2), vec![50, 50], 0, deck).unwrap();
+        let state = play(state, &[Action::AllIn(50), Action::Call]);
+        assert_eq!(state.stacks(), vec![50, 50]);
+    }
+    #[test]
+    fn test_pot_limit_raise_is_capped_at_the_pot() {
+        // Blinds 1/2, calling makes the pot 5 so the largest raise is to 7
+        let config = GameConfig::new(PokerVariant::TexasHoldem, 1, 2).with_structure(BettingStructure::PotLimit);
+        let state = GameState::with_seed(config, vec![100, 100, 100], 2, 7).unwrap();
+        let legal = state.legal_actions().unwrap();
+        assert_eq!(legal.raise, Some(BetRange { min: 4, max: 7 }));
+        assert_eq!(legal.all_in, None);
+    }
+    #[test]
+    fn test_fixed_limit_cap() {
+        // The big blind counts as the bet, three raises later the betting is capped
+        let config = GameConfig::new(PokerVariant::TexasHoldem, 1, 2).with_structure(BettingStructure::FixedLimit { small_bet: 2, big_bet: 4, cap: 4 });
+        let state = GameState::with_seed(config, vec![100, 100, 100], 2, 7).unwrap();
+        assert_eq!(state.legal_actions().unwrap().raise, Some(BetRange { min: 4, max: 4 }));
+        let state = play(state, &[Action::Raise(4), Action::Raise(6), Action::Raise(8)]);
+        let legal = state.legal_actions().unwrap();
+        assert_eq!(legal.call, Some(4));
+        assert_eq!((legal.raise, legal.all_in), (None, None));
+        // The flop is still played with the small bet, the turn with the big bet
+        let state = play(state, &[Action::Call, Action::Call]);
+        assert_eq!(state.legal_actions().unwrap().bet, Some(BetRange { min: 2, max: 2 }));
+        let state = play(state, &[Action::Check, Action::Check, Action::Check]);
+        assert_eq!(state.legal_actions().unwrap().bet, Some(BetRange { min: 4, max: 4 }));
+    }
+    #[test]
+    fn test_bomb_pot_starts_on_the_flop() {
+        // Everyone antes 5 instead of the blinds and the first player left of the button opens the betting on the flop
+        let config = GameConfig::new(PokerVariant::TexasHoldem, 1, 2).with_bomb_pot(5);
+        let state = GameState::with_seed(config, vec![100, 100, 3], 2, 7).unwrap();
+        assert_eq!((state.street(), state.board().len()), (1, 3));
+        assert_eq!((state.pot(), state.stacks()), (13, vec![95, 95, 0]));
+        assert_eq!(state.to_act(), Some(0));
+        assert_eq!(state.legal_actions().unwrap().bet, Some(BetRange { min: 2, max: 95 }));
+        assert!(state.events().contains(&GameEvent::BlindPosted { seat: 2, blind: Blind::BombPot, amount: 3 }));
+        let stud = GameConfig::stud(PokerVariant::SevenCardStud, 1, 2, 10, 20).with_bomb_pot(5);
+        assert_eq!(GameState::with_seed(stud, vec![100, 100], 0, 7).unwrap_err(), RuleError::NoBoard("Seven Card Stud".to_string()));
+    }
+    #[test]
+    fn test_double_board_splits_the_pot() {
+        // Seat 1 gets the first card, then both flops, both turns and both rivers
+        // The aces win the first board and the kings make trips on the second
+        let deck = Deck::stacked("Ks As Kd Ad 2c 7h 9d Kh 8c 5d 3s Jh 4c Qc");
+        let config = GameConfig::new(PokerVariant::TexasHoldem, 1, 2).with_boards(2);
+        let state = GameState::new(config, vec![100, 100], 0, deck).unwrap().with_runouts_offered(2);
+        let state = play(state, &[Action::AllIn(100), Action::Call]);
+        assert!(!state.is_awaiting_runouts());
+        assert_eq!(state.boards(), &[parse_cards("2c 7h 9d 3s 4c").unwrap(), parse_cards("Kh 8c 5d Jh Qc").unwrap()]);
+        let result = state.result().unwrap();
+        assert_eq!(result.runouts.len(), 2);
+        assert_eq!((result.runouts[0].pots[0].amount, result.runouts[0].pots[0].winners.clone()), (100, vec![0]));
+        assert_eq!((result.runouts[1].pots[0].amount, result.runouts[1].pots[0].winners.clone()), (100, vec![1]));
+        assert_eq!(state.stacks(), vec![100, 100]);
+    }
+
+    // Stud with an ante of 1, a bring-in of 2 and limits of 10/20, seat 0 gets the first card
+    fn stud(variant: PokerVariant, stacks: Vec<u32>, cards: &str) -> GameState {
+        let button = stacks.len() - 1;
+        GameState::new(GameConfig::stud(variant, 1, 2, 10, 20), stacks, button, Deck::stacked(cards)).unwrap()
+    }
+
+    #[test]
+    fn test_stud_bring_in() {
+        // Door cards 9c 2d 2c, the deuce of clubs is the lowest and brings it in
+        let state = stud(PokerVariant::SevenCardStud, vec![100, 100, 100], "Ah Kh Qh As Ks Qs 9c 2d 2c");
+        assert_eq!(state.hole_cards(0), parse_cards("Ah As").unwrap().as_slice());
+        assert_eq!(state.up_cards(2), parse_cards("2c").unwrap().as_slice());
+        assert_eq!(state.cards_seen_by(0, 0), parse_cards("Ah As 9c").unwrap());
+        assert_eq!(state.cards_seen_by(1, 0), parse_cards("9c").unwrap());
+        assert_eq!(state.pot(), 3);
+        let legal = state.legal_actions().unwrap();
+        assert_eq!(legal.seat, 2);
+        assert_eq!(legal.bring_in, Some(2));
+        assert_eq!(legal.bet, Some(BetRange { min: 10, max: 10 }));
+        assert!(!legal.can_fold() && !legal.can_check);
+        assert_eq!(legal.actions(), vec![Action::Bet(2), Action::Bet(10)]);
+        assert_eq!(state.validate(&Action::Fold), Err(RuleError::MustBringIn(2)));
+        assert_eq!(state.validate(&Action::Bet(5)), Err(RuleError::BelowMinimum { amount: 5, min: 10 }));
+
+        // Seat 0 completes to 10, the bring-in may then raise to 20
+        let state = play(state, &[Action::Bet(2), Action::Raise(10), Action::Call]);
+        let legal = state.legal_actions().unwrap();
+        assert_eq!(legal.seat, 2);
+        assert_eq!(legal.call, Some(8));
+        assert_eq!(legal.raise, Some(BetRange { min: 20, max: 20 }));
+    }
+    #[test]
+    fn test_stud_best_hand_showing_acts_first() {
+        // Seat 1 pairs its door card on fourth street and acts first there with the small bet
+        let state = stud(PokerVariant::SevenCardStud, vec![100, 100, 100], "Ah Kh Qh As Ks Qs 9c 2d 2c 3h 2h Jd");
+        let state = play(state, &[Action::Bet(2), Action::Call, Action::Call]);
+        assert_eq!(state.street(), 1);
+        assert_eq!(state.up_cards(1), parse_cards("2d 2h").unwrap().as_slice());
+        let legal = state.legal_actions().unwrap();
+        assert_eq!(legal.seat, 1);
+        assert_eq!(legal.bring_in, None);
+        assert_eq!(legal.bet, Some(BetRange { min: 10, max: 10 }));
+    }
+    #[test]
+    fn test_razz_high_card_brings_it_in_and_low_acts_first() {
+        // Door cards Kc 2d Ad, the king brings it in, then seat 2 shows A2 and opens fourth street
+        let state = stud(PokerVariant::Razz, vec![100, 100, 100], "Ah Kh Qh As Ks Qs Kc 2d Ad 3h 9c 2h");
+        assert_eq!(state.legal_actions().unwrap().seat, 0);
+        let state = play(state, &[Action::Bet(2), Action::Call, Action::Call]);
+        assert_eq!(state.street(), 1);
+        assert_eq!(state.legal_actions().unwrap().seat, 2);
+    }
+    #[test]
+    fn test_stud_river_is_a_common_card_when_the_deck_runs_out() {
+        // Eight players use 48 cards by sixth street, the last 4 cards can't go round so one is shared
+        let config = GameConfig::stud(PokerVariant::SevenCardStud, 1, 2, 10, 20);
+        let mut state = GameState::with_seed(config, vec![500; 8], 0, 11).unwrap();
+        while let Some(legal) = state.legal_actions() {
+            let action = match legal.bring_in {
+                Some(amount) => Action::Bet(amount),
+                None if legal.can_check => Action::Check,
+                None => Action::Call,
+            };
+            state = state.apply(action).unwrap();
+        }
+        assert_eq!(state.board().len(), 1);
+        assert!((0..8).all(|seat| state.hole_cards(seat).len() == 2 && state.up_cards(seat).len() == 4));
+        assert_eq!(state.stacks().iter().sum::<u32>(), 4000);
+    }
+    #[test]
+    fn test_stud_hi_lo_splits_the_pot() {
+        // Seat 0 makes kings full, seat 1 a seven low, seat 2 folds its ante
+        let cards = "Kh Ah 5c Kd 2c 6c Kc 3d 8s 9s 4s 9h 7h Qs Jc Td Qd";
+        let state = stud(PokerVariant::SevenCardStudHiLo, vec![100, 100, 100], cards);
+        let state = play(state, &[Action::Bet(2), Action::Fold, Action::Call]);
+        let state = play(state, &[Action::Check; 8]);
+        let result = state.result().unwrap();
+        assert_eq!(result.hands[0].unwrap().rank(), HandRank::FullHouse);
+        assert_eq!(result.lows[1].unwrap().values(), &[7, 4, 3, 2, 1]);
+        assert_eq!(result.lows[0], None);
+        assert_eq!((result.pots[0].winners.clone(), result.pots[0].low_winners.clone()), (vec![0], vec![1]));
+        // The odd chip of the pot of 7 goes to the high half
+        assert_eq!(result.winnings, vec![4, 3, 0]);
+    }
+    #[test]
+    fn test_five_card_draw() {
+        // Seat 1 gets the first card, seat 0 keeps a pair of deuces and draws three to make trips
+        let deck = Deck::stacked("As 2c Ks 2d Qd 7h Jc 8s 9h 4c 2h 3s 5d");
+        let state = GameState::new(GameConfig::new(PokerVariant::FiveCard, 1, 2), vec![100, 100], 0, deck).unwrap();
+        let state = play(state, &[Action::Call, Action::Check]);
+        assert!(state.is_drawing());
+        let legal = state.legal_actions().unwrap();
+        assert_eq!((legal.seat, legal.draw), (1, Some(5)));
+        assert!(!legal.can_fold());
+        assert_eq!(legal.actions().len(), 32);
+        assert_eq!(state.validate(&Action::Check), Err(RuleError::MustDraw));
+        assert_eq!(state.validate(&Action::Draw(Discards::from_positions(&[5]))), Err(RuleError::InvalidDiscards));
+
+        let state = play(state, &[Action::Draw(Discards::none()), Action::Draw(Discards::from_positions(&[2, 3, 4]))]);
+        assert!(!state.is_drawing());
+        assert_eq!(state.hole_cards(0), parse_cards("2c 2d 2h 3s 5d").unwrap().as_slice());
+        assert_eq!(state.validate(&Action::Draw(Discards::none())), Err(RuleError::NotDrawing));
+        let state = play(state, &[Action::Check, Action::Check]);
+        assert_eq!(state.result().unwrap().hands[0].unwrap().rank(), HandRank::ThreeOfAKind);
+        assert_eq!(state.stacks(), vec![102, 98]);
+    }
+    #[test]
+    fn test_state_from_an_observation() {
+        // Rebuilt with the real cards, the state plays on just as the real one does
+        let state = play(holdem(vec![100, 100, 100]), &[Action::Raise(6), Action::Call]);
+        let seat = state.to_act().unwrap();
+        let hands = (0..3).map(|other| state.hole_cards(other).to_vec()).collect();
+        let rebuilt = GameState::from_observation(&Observation::new(&state, seat), hands, state.deck.clone()).unwrap();
+        assert_eq!(rebuilt.legal_actions(), state.legal_actions());
+        assert_eq!(rebuilt.pot(), state.pot());
+        let actions = [Action::Call, Action::Check, Action::Bet(10), Action::Call, Action::Fold];
+        assert_eq!(play(rebuilt, &actions).stacks(), play(state.clone(), &actions).stacks());
+        assert_eq!(GameState::from_observation(&Observation::new(&state, seat), Vec::new(), Deck::from_cards(Vec::new())).err(), Some(RuleError::WrongHands(3)));
+        // Halfway through a draw the seats that haven't drawn are still to
+        let deck = Deck::stacked("As 2c Ks 2d Qd 7h Jc 8s 9h 4c 2h 3s 5d");
+        let state = GameState::new(GameConfig::new(PokerVariant::FiveCard, 1, 2), vec![100, 100], 0, deck).unwrap();
+        let state = play(state, &[Action::Call, Action::Check, Action::Draw(Discards::none())]);
+        let hands = vec![Vec::new(), parse_cards("As Ks Qd Jc 9h").unwrap()];
+        let rebuilt = GameState::from_observation(&Observation::new(&state, 0), hands, state.deck.clone()).unwrap();
+        assert!(rebuilt.is_drawing());
+        assert_eq!(rebuilt.legal_actions(), state.legal_actions());
+    }
+    #[test]
+    fn test_draw_reshuffles_the_discards() {
+        // Two cards are left after the deal, seat 1 takes them so seat 0 draws from seat 1's discards
+        let deck = Deck::stacked("As 2c Ks 2d Qd 7h Jc 8s 9h 4c 2h 3s");
+        let state = GameState::new(GameConfig::new(PokerVariant::FiveCard, 1, 2), vec![100, 100], 0, deck).unwrap();
+        let state = play(state, &[Action::Call, Action::Check, Action::Draw(Discards::from_positions(&[0, 1]))]);
+        let state = state.apply(Action::Draw(Discards::from_positions(&[3, 4]))).unwrap();
+        let mut drawn = state.hole_cards(0)[3..].to_vec();
+        drawn.sort_by_key(|card| card.rank());
+        assert_eq!(drawn, parse_cards("Ks As").unwrap());
+    }
+    #[test]
+    fn test_triple_draw_lowest_hand_wins() {
+        // Seat 1 has a seven low, seat 0 a straight which is a bad low
+        let deck = Deck::stacked("7h 8h 5d 7d 4c 6c 3s 5s 2h 4d");
+        let state = GameState::new(GameConfig::new(PokerVariant::TwoSevenTripleDraw, 1, 2), vec![100, 100], 0, deck).unwrap();
+        let mut state = play(state, &[Action::Call, Action::Check]);
+        for _ in 0..3 {
+            state = play(state, &[Action::Draw(Discards::none()), Action::Draw(Discards::none()), Action::Check, Action::Check]);
+        }
+        let result = state.result().unwrap();
+        assert_eq!(result.hands, vec![None, None]);
+        assert_eq!(result.pots[0].low_winners, vec![1]);
+        assert_eq!(result.winnings, vec![0, 4]);
+    }
+    // Kuhn poker dealt from the given cards, seat 0 gets the first card and acts first
+    fn kuhn(cards: &str) -> GameState {
+        GameState::new(GameConfig::kuhn(), vec![10, 10], 1, Deck::stacked(cards)).unwrap()
+    }
+
+    #[test]
+    fn test_kuhn_actions() {
+        let state = kuhn("Ks Qs Js");
+        assert_eq!(state.pot(), 2);
+        let legal = state.legal_actions().unwrap();
+        assert_eq!(legal.seat, 0);
+        assert_eq!(legal.actions(), vec![Action::Check, Action::Bet(1)]);
+        // A bet can only be called or folded
+        let legal = state.apply(Action::Bet(1)).unwrap().legal_actions().unwrap();
+        assert_eq!(legal.actions(), vec![Action::Fold, Action::Call]);
+    }
+    #[test]
+    fn test_kuhn_utilities() {
+        let utilities = |cards: &str, actions: &[Action]| {
+            let state = play(kuhn(cards), actions);
+            assert!(state.is_terminal());
+            (state.utility(0).unwrap(), state.utility(1).unwrap())
+        };
+        assert_eq!(utilities("Ks Qs", &[Action::Check, Action::Check]), (1, -1));
+        assert_eq!(utilities("Ks Qs", &[Action::Bet(1), Action::Fold]), (1, -1));
+        assert_eq!(utilities("Qs Ks", &[Action::Bet(1), Action::Call]), (-2, 2));
+        assert_eq!(utilities("Ks Js", &[Action::Check, Action::Bet(1), Action::Fold]), (-1, 1));
+        assert_eq!(utilities("Ks Js", &[Action::Check, Action::Bet(1), Action::Call]), (2, -2));
+        assert_eq!(kuhn("Ks Qs").utility(0), None);
+    }
+    #[test]
+    fn test_kuhn_is_two_players_with_three_cards() {
+        let state = GameState::with_seed(GameConfig::kuhn(), vec![5, 5], 0, 3).unwrap();
+        assert_ne!(state.hole_cards(0)[0], state.hole_cards(1)[0]);
+        assert!(state.hole_cards(0)[0].rank() >= 11);
+        let too_many = GameState::with_seed(GameConfig::kuhn(), vec![5, 5, 5], 0, 3);
+        assert_eq!(too_many.err(), Some(RuleError::TooManyPlayers(2)));
+    }
+    #[test]
+    fn test_too_many_players_for_the_deck() {
+        // Eleven five card hands don't fit in the deck, ten do
+        let draw = GameConfig::new(PokerVariant::FiveCard, 1, 2);
+        assert_eq!(GameState::with_seed(draw.clone(), vec![100; 11], 0, 3).err(), Some(RuleError::TooManyPlayers(10)));
+        assert!(GameState::with_seed(draw, vec![100; 10], 0, 3).is_ok());
+        let holdem = GameConfig::new(PokerVariant::TexasHoldem, 1, 2);
+        assert_eq!(GameState::with_seed(holdem, vec![100; 24], 0, 3).err(), Some(RuleError::TooManyPlayers(23)));
+        let stud = GameConfig::stud(PokerVariant::SevenCardStud, 1, 2, 10, 20);
+        assert_eq!(GameState::with_seed(stud, vec![100; 9], 0, 3).err(), Some(RuleError::TooManyPlayers(8)));
+    }
+    #[test]
+    fn test_eight_handed_stud_shares_the_river() {
+        // 48 cards are out by sixth street, the 4 left can't give eight players a card each so one goes in the middle
+        let stud = GameConfig::stud(PokerVariant::SevenCardStud, 1, 2, 10, 20);
+        let mut state = GameState::with_seed(stud, vec![100; 8], 7, 3).unwrap().with_audit();
+        while state.street() < 4 {
+            let legal = state.legal_actions().unwrap();
+            let action = match (legal.bring_in, legal.call) {
+                (Some(bring_in), _) => Action::Bet(bring_in),
+                (None, Some(_)) => Action::Call,
+                (None, None) => Action::Check,
+            };
+            state = state.apply(action).unwrap();
+        }
+        assert_eq!(state.board().len(), 1);
+        assert!((0..8).all(|seat| state.hole_cards(seat).len() == 2 && state.up_cards(seat).len() == 4));
+    }
+    #[test]
+    fn test_leduc_betting() {
+        // Seat 0 gets Kh, seat 1 Qs, the board is Qh
+        let state = GameState::new(GameConfig::leduc(), vec![20, 20], 1, Deck::stacked("Kh Qs Qh")).unwrap();
+        assert_eq!(state.legal_actions().unwrap().bet, Some(BetRange { min: 2, max: 2 }));
+        // A bet and a raise reach the cap of two
+        let state = play(state, &[Action::Bet(2), Action::Raise(4)]);
+        let legal = state.legal_actions().unwrap();
+        assert_eq!((legal.call, legal.raise), (Some(2), None));
+        let state = play(state, &[Action::Call]);
+        assert_eq!(state.board(), parse_cards("Qh").unwrap().as_slice());
+        assert_eq!(state.legal_actions().unwrap().bet, Some(BetRange { min: 4, max: 4 }));
+        // The pair of queens beats the king
+        let state = play(state, &[Action::Bet(4), Action::Call]);
+        assert_eq!(state.result().unwrap().hands[1].unwrap().rank(), HandRank::OnePair);
+        assert_eq!((state.utility(0), state.utility(1)), (Some(-9), Some(9)));
+    }
+    #[test]
+    fn test_leduc_deck() {
+        let state = GameState::with_seed(GameConfig::leduc(), vec![20, 20], 0, 5).unwrap();
+        let state = play(state, &[Action::Check, Action::Check]);
+        let cards = [state.hole_cards(0), state.hole_cards(1), state.board()].concat();
+        assert_eq!(cards.iter().collect::<std::collections::HashSet<_>>().len(), 3);
+        assert!(cards.iter().all(|card| card.rank() >= 11 && matches!(card.suit(), Suit::Hearts | Suit::Spades)));
+    }
+    #[test]
+    fn test_unsupported_variant() {
+        let config = GameConfig::new(PokerVariant::ThreeCard, 1, 2);
+        assert!(matches!(GameState::with_seed(config, vec![10, 10], 0, 1), Err(RuleError::UnsupportedVariant(_))));
+    }
+}
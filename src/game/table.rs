@@ -0,0 +1,536 @@
+use crate::game::blinds::{MissedBlinds, Posts, Straddle};
+use crate::game::config::GameConfig;
+use crate::game::events::{GameEvent, Observer};
+use crate::game::observation::Observation;
+use crate::game::rake::Rake;
+use crate::game::rotation::Rotation;
+use crate::game::state::{GameState, HandResult, RuleError};
+use crate::game::timer::ActionTimer;
+use crate::players::player::Player;
+use rand::Rng;
+
+// Times a player is asked for a decision the rules allow before they check or fold instead,
+// so a bot that keeps breaking the rules can't hold up the game
+pub const MAX_ATTEMPTS: usize = 3;
+
+// Players sitting at a table, hands are played one after another with the game state applying the rules
+// The table asks the players for their decisions showing each only what their seat can see, the chips are only ever moved by the game state
+#[derive(Debug)]
+pub struct Table {
+    config: GameConfig,
+    players: Vec<Player>,
+    stacks: Vec<u32>,
+    away: Vec<bool>,             // Sitting out, kept out of the hands until they come back
+    missed: Vec<MissedBlinds>,   // Blinds each seat owes, posted in the next hand they are dealt into
+    straddle: Option<Straddle>,  // Straddle for the next hand
+    bomb_pot: Option<u32>,       // Everyone's ante when the next hand is a bomb pot
+    timer: Option<ActionTimer>,  // Clock on the players' decisions, None for no time limit
+    runouts: usize,              // Most times the board is run when everyone is all in and agrees to it
+    audit: bool,                 // Check every hand against its chip ledger
+    observers: Vec<Box<dyn Observer>>, // Told everything that happens in the hands
+    rotation: Option<Rotation>,  // Games of a mixed game, None to always play the same game
+    base_rake: Option<Rake>,     // Rake of the table's own rules, taken in the rotation's games that set none
+    button: usize,
+}
+
+impl Table {
+    pub fn new(config: GameConfig) -> Self {
+        let base_rake = config.rake.clone();
+        Table { config, base_rake, players: Vec::new(), stacks: Vec::new(), away: Vec::new(), missed: Vec::new(), straddle: None, bomb_pot: None, timer: None, runouts: 1, audit: false, observers: Vec::new(), rotation: None, button: 0 }
+    }
+
+    // Sits a player down in the next seat with chips in front of them
+    pub fn seat(&mut self, player: Player, chips: u32) {
+        self.players.push(player);
+        self.stacks.push(chips);
+        self.away.push(false);
+        self.missed.push(MissedBlinds::default());
+        if let Some(timer) = self.timer.as_mut() {
+            timer.add_seat();
+        }
+    }
+
+    // Puts the players on the clock, everyone seated gets a full time bank
+    pub fn set_timer(&mut self, mut timer: ActionTimer) {
+        for _ in &self.players {
+            timer.add_seat();
+        }
+        self.timer = Some(timer);
+    }
+
+    pub fn timer(&self) -> Option<&ActionTimer> {
+        self.timer.as_ref()
+    }
+
+    // Puts more chips in front of a seat, for a rebuy or top up between hands
+    pub fn add_chips(&mut self, seat: usize, chips: u32) {
+        self.stacks[seat] += chips;
+    }
+
+    // Keeps a seat out of the hands, their chips stay on the table
+    pub fn sit_out(&mut self, seat: usize) {
+        self.away[seat] = true;
+    }
+
+    // Deals a seat back in from the next hand
+    pub fn sit_in(&mut self, seat: usize) {
+        self.away[seat] = false;
+    }
+
+    pub fn is_sitting_out(&self, seat: usize) -> bool {
+        self.away[seat]
+    }
+
+    // Has a seat post a big blind in the next hand it is dealt into, unless it is in the blinds anyway
+    pub fn post_big_blind(&mut self, seat: usize) {
+        self.missed[seat].big = true;
+    }
+
+    // Blinds a seat will have to post when it is next dealt in
+    pub fn missed_blinds(&self, seat: usize) -> MissedBlinds {
+        self.missed[seat]
+    }
+
+    // Offers to run the board up to times times when everyone is all in, 1 to always run it once
+    pub fn set_runouts(&mut self, times: usize) {
+        self.runouts = times.max(1);
+    }
+
+    // Checks the chips after every action against the hand's ledger, and that only the rake leaves the table,
+    // panicking if chips are ever made or lost
+    pub fn set_audit(&mut self, audit: bool) {
+        self.audit = audit;
+    }
+
+    // Adds an observer that is sent the events of every hand from now on
+    pub fn subscribe(&mut self, observer: Box<dyn Observer>) {
+        self.observers.push(observer);
+    }
+
+    // Plays a mixed game, the rotation decides the rules of every hand from now on
+    // A rake set in the table's rules is kept for games of the rotation that have none
+    pub fn set_rotation(&mut self, rotation: Rotation) {
+        self.rotation = Some(rotation);
+    }
+
+    pub fn rotation(&self) -> Option<&Rotation> {
+        self.rotation.as_ref()
+    }
+
+    // Straddle in the next hand only
+    pub fn straddle(&mut self, straddle: Straddle) {
+        self.straddle = Some(straddle);
+    }
+
+    // Makes the next hand only a bomb pot: everyone antes amount and the betting starts on the flop,
+    // nobody posts a blind, so blinds owed and a straddle wait for the hand after
+    pub fn bomb_pot(&mut self, amount: u32) {
+        self.bomb_pot = Some(amount);
+    }
+
+    // Whether a seat will be dealt into the next hand, it needs chips and to not be sitting out
+    pub fn is_dealt_in(&self, seat: usize) -> bool {
+        self.stacks[seat] > 0 && !self.away[seat]
+    }
+
+    // Rules the hands are played with
+    pub fn config(&self) -> &GameConfig {
+        &self.config
+    }
+
+    // Takes a player and their chips off the table, the seats after it move up one and the button stays with the same player
+    // (or passes to the next seat if it was the player leaving)
+    pub fn remove(&mut self, seat: usize) -> (Player, u32) {
+        let player = self.players.remove(seat);
+        let chips = self.stacks.remove(seat);
+        self.away.remove(seat);
+        self.missed.remove(seat);
+        if let Some(timer) = self.timer.as_mut() {
+            timer.remove_seat(seat);
+        }
+        if seat < self.button {
+            self.button -= 1;
+        }
+        if self.button >= self.players.len() {
+            self.button = 0;
+        }
+        (player, chips)
+    }
+
+    // Seat that will post the big blind next hand, the one after the button heads up and the second after it otherwise
+    pub fn big_blind_seat(&self) -> Option<usize> {
+        self.blinds_among(|seat| self.is_dealt_in(seat)).map(|(_, big)| big)
+    }
+
+    // Whether a seat that is sitting out would be the big blind next hand if it were dealt in,
+    // players waiting for the big blind come in then
+    pub fn would_be_big_blind(&self, seat: usize) -> bool {
+        self.blinds_if_dealt_in(seat).is_some_and(|(_, big)| big == seat)
+    }
+
+    // Small and big blind seats if seat were dealt in too
+    fn blinds_if_dealt_in(&self, seat: usize) -> Option<(usize, usize)> {
+        if self.stacks[seat] == 0 {
+            return None;
+        }
+        self.blinds_among(|other| other == seat || self.is_dealt_in(other))
+    }
+
+    // Small and big blind seats if the seats for which dealt is true are dealt in
+    fn blinds_among(&self, dealt: impl Fn(usize) -> bool) -> Option<(usize, usize)> {
+        let num_seats = self.stacks.len();
+        let players = (0..num_seats).filter(|&seat| dealt(seat)).count();
+        let mut order = (1..=num_seats).map(|offset| (self.button + offset) % num_seats).filter(|&seat| dealt(seat));
+        if players == 2 && dealt(self.button) {
+            return order.next().map(|big| (self.button, big));
+        }
+        Some((order.next()?, order.next()?))
+    }
+
+    // Seats sitting out with chips miss the blinds that would have been theirs
+    fn mark_missed_blinds(&mut self) {
+        for seat in 0..self.stacks.len() {
+            if !self.away[seat] {
+                continue;
+            }
+            if let Some((small, big)) = self.blinds_if_dealt_in(seat) {
+                self.missed[seat].small |= small == seat;
+                self.missed[seat].big |= big == seat;
+            }
+        }
+    }
+
+    // Changes the rules for the next hands, e.g. when the blinds go up
+    pub fn set_config(&mut self, config: GameConfig) {
+        self.base_rake = config.rake.clone();
+        self.config = config;
+    }
+
+    // Players in seat order
+    pub fn players(&self) -> &[Player] {
+        &self.players
+    }
+
+    // Chips in front of each seat
+    pub fn stacks(&self) -> &[u32] {
+        &self.stacks
+    }
+
+    // Seat with the dealer button
+    pub fn button(&self) -> usize {
+        self.button
+    }
+
+    // Number of players with chips left
+    pub fn players_with_chips(&self) -> usize {
+        self.stacks.iter().filter(|&&stack| stack > 0).count()
+    }
+
+    // Number of players who will be dealt into the next hand
+    pub fn players_dealt_in(&self) -> usize {
+        (0..self.stacks.len()).filter(|&seat| self.is_dealt_in(seat)).count()
+    }
+
+    // Plays one hand with a deck shuffled by rng, then moves the button on
+    // Players sitting out are left out of the hand as if they had no chips
+    pub fn play_hand<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Result<HandResult, RuleError> {
+        // In a mixed game the player on the button picks the game in dealer's choice
+        let players = self.players_dealt_in();
+        if let Some(rotation) = self.rotation.as_mut() {
+            let game = rotation.next_hand(players, |games| self.players[self.button].choose_game(games));
+            self.config = GameConfig { rake: game.rake.clone().or(self.base_rake.clone()), ..game.clone() };
+        }
+        let deck = self.config.variant.shuffled_deck(rng).map_err(|_| RuleError::NotEnoughCards)?;
+        let dealt_in: Vec<bool> = (0..self.stacks.len()).map(|seat| self.is_dealt_in(seat)).collect();
+        let stacks = (0..self.stacks.len()).map(|seat| if dealt_in[seat] { self.stacks[seat] } else { 0 }).collect();
+        let bomb_pot = self.bomb_pot.take();
+        let config = match bomb_pot {
+            Some(amount) => self.config.clone().with_bomb_pot(amount),
+            None => self.config.clone(),
+        };
+        let mut posts = Posts::none();
+        if bomb_pot.is_none() {
+            self.mark_missed_blinds();
+            posts = Posts::live((0..self.stacks.len()).filter(|&seat| dealt_in[seat] && self.missed[seat].big).collect())
+                .with_dead((0..self.stacks.len()).filter(|&seat| dealt_in[seat] && self.missed[seat].small).collect());
+            posts.straddle = self.straddle.take();
+        }
+        let mut state = GameState::with_posts(config, stacks, self.button, deck, &posts)?
+            .with_shuffle_seed(rng.gen())
+            .with_runouts_offered(self.runouts);
+        if self.audit {
+            state = state.with_audit();
+        }
+
+        let names: Vec<String> = self.players.iter().map(|player| player.get_name().to_string()).collect();
+        for observer in self.observers.iter_mut() {
+            observer.on_players(&names);
+        }
+        let mut published = 0;
+        self.publish(&state, &mut published);
+        let mut attempts = 0;
+        while let Some(legal) = state.legal_actions() {
+            if let Some(timer) = self.timer.as_mut() {
+                timer.start(legal.seat);
+            }
+            // The player only gets to see what their seat can see, and how long they have to decide
+            // Agents are expected to answer before their time is up (the human player stops waiting for input then)
+            let mut observation = Observation::new(&state, legal.seat);
+            observation.time_left = self.timer.as_ref().map(|timer| timer.time_left(legal.seat));
+            let mut action = self.players[legal.seat].decide(&observation, &legal).action(&legal);
+            // A decision made after the player's time ran out doesn't count, they check or fold instead
+            if self.timer.as_ref().is_some_and(|timer| timer.is_out_of_time(legal.seat)) {
+                self.notify(&GameEvent::TimedOut { seat: legal.seat });
+                action = legal.default_action();
+            }
+            // Illegal actions are rejected and the player is asked again, after too many they check or fold
+            let next = match state.apply(action) {
+                Ok(next) => next,
+                Err(error) => {
+                    self.notify(&GameEvent::ActionRejected { seat: legal.seat, action, error });
+                    attempts += 1;
+                    if attempts < MAX_ATTEMPTS {
+                        continue;
+                    }
+                    state.apply(legal.default_action())?
+                }
+            };
+            if let Some(timer) = self.timer.as_mut() {
+                timer.stop(legal.seat);
+            }
+            attempts = 0;
+            state = next;
+            self.publish(&state, &mut published);
+        }
+        // Everyone is all in, the board is run as many times as offered if all the players still in agree
+        if state.is_awaiting_runouts() {
+            let agreed = state.in_hand().into_iter().all(|seat| self.players[seat].agree_to_runouts(self.runouts));
+            state = state.run_out(if agreed { self.runouts } else { 1 })?;
+            self.publish(&state, &mut published);
+        }
+
+        let result = state.result().cloned().ok_or(RuleError::NoPlayerToAct)?;
+        if self.audit {
+            let before: u32 = state.ledger().starting().iter().sum();
+            assert_eq!(before, state.stacks().iter().sum::<u32>() + result.rake, "Chips left the table other than as rake");
+        }
+        for (seat, stack) in state.stacks().into_iter().enumerate().filter(|&(seat, _)| dealt_in[seat]) {
+            self.stacks[seat] = stack;
+            if bomb_pot.is_none() {
+                self.missed[seat] = MissedBlinds::default();
+            }
+        }
+        self.move_button();
+        Ok(result)
+    }
+
+    // Sends the observers an event of the table rather than of the hand
+    fn notify(&mut self, event: &GameEvent) {
+        for observer in self.observers.iter_mut() {
+            observer.on_event(event);
+        }
+    }
+
+    // Sends the observers the events of the hand they have not been sent yet
+    fn publish(&mut self, state: &GameState, published: &mut usize) {
+        for event in &state.events()[*published..] {
+            for observer in self.observers.iter_mut() {
+                observer.on_event(event);
+            }
+        }
+        *published = state.events().len();
+    }
+
+    // Moves the button to the next seat that will be dealt in
+    fn move_button(&mut self) {
+        let num_seats = self.stacks.len();
+        if let Some(next) = (1..=num_seats).map(|offset| (self.button + offset) % num_seats).find(|&seat| self.is_dealt_in(seat)) {
+            self.button = next;
+        }
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use std::time::Duration;
+    use crate::game::clock::ManualClock;
+    use crate::game::events::EventLog;
+    use crate::game::timer::TimeControl;
+    use crate::game::legal::LegalActions;
+    use crate::game::rotation::RotationLength;
+    use crate::game::variant::PokerVariant;
+    use crate::players::action::Action;
+    use crate::players::base::{Agent, Decision};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    // Agent that never puts in a chip it doesn't have to
+    #[derive(Debug)]
+    struct CheckFolder;
+
+    impl Agent for CheckFolder {
+        fn name(&self) -> &str {
+            "Check folder"
+        }
+
+        fn decide(&mut self, _observation: &Observation, _legal: &LegalActions) -> Decision {
+            Decision::CheckOrFold
+        }
+    }
+
+    #[test]
+    fn test_waiting_player_comes_in_as_the_big_blind() {
+        let mut table = Table::new(GameConfig::new(PokerVariant::TexasHoldem, 1, 2));
+        for i in 0..4 {
+            table.seat(Player::new_human_player(format!("Player {}", i + 1)), 100);
+        }
+        table.sit_out(3);
+        // Button in seat 0: seat 1 is the small blind and seat 2 the big blind with or without seat 3
+        assert_eq!(table.big_blind_seat(), Some(2));
+        assert!(!table.would_be_big_blind(3));
+        // Button in seat 1: seat 3 would be the big blind
+        table.button = 1;
+        assert_eq!(table.big_blind_seat(), Some(0));
+        assert!(table.would_be_big_blind(3));
+    }
+    #[test]
+    fn test_missed_blinds() {
+        let mut table = Table::new(GameConfig::new(PokerVariant::TexasHoldem, 1, 2));
+        for i in 0..4 {
+            table.seat(Player::new_human_player(format!("Player {}", i + 1)), 100);
+        }
+        table.sit_out(3);
+        table.button = 1;
+        table.mark_missed_blinds();
+        assert_eq!(table.missed_blinds(3), MissedBlinds { small: false, big: true });
+        // Next hand the blind would have been the small blind
+        table.button = 2;
+        table.mark_missed_blinds();
+        assert_eq!(table.missed_blinds(3), MissedBlinds { small: true, big: true });
+        // Seats playing and seats with no chips owe nothing
+        assert!(!table.missed_blinds(0).any());
+        table.sit_in(3);
+        assert!(table.missed_blinds(3).any());
+    }
+    #[test]
+    fn test_illegal_actions_are_rejected_then_check_or_fold() {
+        // Raising to 1 is never legal, the small blind is asked MAX_ATTEMPTS times and then folds
+        #[derive(Debug)]
+        struct RaisesToOne;
+
+        impl Agent for RaisesToOne {
+            fn name(&self) -> &str {
+                "Raises to one"
+            }
+
+            fn decide(&mut self, _observation: &Observation, _legal: &LegalActions) -> Decision {
+                Decision::Act(Action::Raise(1))
+            }
+        }
+
+        let mut table = Table::new(GameConfig::new(PokerVariant::TexasHoldem, 1, 2));
+        table.seat(Player::new(RaisesToOne), 100);
+        table.seat(Player::new(RaisesToOne), 100);
+        let log = Rc::new(RefCell::new(EventLog::new()));
+        table.subscribe(Box::new(log.clone()));
+        table.play_hand(&mut StdRng::seed_from_u64(1)).unwrap();
+        assert_eq!(table.stacks(), &[99, 101]);
+        let rejected: Vec<GameEvent> = log.borrow().events().iter().filter(|event| matches!(event, GameEvent::ActionRejected { .. })).cloned().collect();
+        let error = RuleError::BelowMinimum { amount: 1, min: 4 };
+        assert_eq!(rejected, vec![GameEvent::ActionRejected { seat: 0, action: Action::Raise(1), error }; MAX_ATTEMPTS]);
+        assert!(log.borrow().events().contains(&GameEvent::ActionTaken { street: 0, seat: 0, action: Action::Fold }));
+    }
+    #[test]
+    fn test_slow_decisions_time_out_and_check_or_fold() {
+        // Takes 50 seconds to raise, past the 10 seconds a decision and 30 second bank, and notes the time it was given
+        #[derive(Debug)]
+        struct SlowRaiser {
+            clock: ManualClock,
+            given: Rc<RefCell<Vec<Option<Duration>>>>,
+        }
+
+        impl Agent for SlowRaiser {
+            fn name(&self) -> &str {
+                "Slow raiser"
+            }
+
+            fn decide(&mut self, observation: &Observation, _legal: &LegalActions) -> Decision {
+                self.given.borrow_mut().push(observation.time_left);
+                self.clock.advance(Duration::from_secs(50));
+                Decision::Act(Action::Raise(6))
+            }
+        }
+
+        let clock = ManualClock::new();
+        let given = Rc::new(RefCell::new(Vec::new()));
+        let mut table = Table::new(GameConfig::new(PokerVariant::TexasHoldem, 1, 2));
+        table.seat(Player::new(SlowRaiser { clock: clock.clone(), given: given.clone() }), 100);
+        table.seat(Player::new(CheckFolder), 100);
+        let control = TimeControl { per_action: Duration::from_secs(10), time_bank: Duration::from_secs(30) };
+        table.set_timer(ActionTimer::new(control).with_clock(Box::new(clock)));
+        let log = Rc::new(RefCell::new(EventLog::new()));
+        table.subscribe(Box::new(log.clone()));
+        table.play_hand(&mut StdRng::seed_from_u64(1)).unwrap();
+        // The raise came in too late, so the small blind folds and its bank is used up
+        assert_eq!(*given.borrow(), vec![Some(Duration::from_secs(40))]);
+        assert_eq!(table.stacks(), &[99, 101]);
+        let events = log.borrow().events().to_vec();
+        let timed_out = events.iter().position(|event| *event == GameEvent::TimedOut { seat: 0 }).unwrap();
+        assert_eq!(events[timed_out + 1], GameEvent::ActionTaken { street: 0, seat: 0, action: Action::Fold });
+        assert_eq!(table.timer().unwrap().bank(0), Duration::ZERO);
+    }
+    #[test]
+    fn test_agents_decide_and_the_table_moves_the_chips() {
+        // The agents check or fold, so the big blind wins the blinds and a bomb pot is checked down to a showdown
+        let mut table = Table::new(GameConfig::new(PokerVariant::TexasHoldem, 1, 2));
+        for _ in 0..3 {
+            table.seat(Player::new(CheckFolder), 100);
+        }
+        let mut rng = StdRng::seed_from_u64(7);
+        let result = table.play_hand(&mut rng).unwrap();
+        assert_eq!(table.stacks(), &[100, 99, 101]);
+        assert_eq!(result.winnings, vec![0, 0, 2]);
+        table.bomb_pot(10);
+        let result = table.play_hand(&mut rng).unwrap();
+        assert!(result.hands.iter().all(|hand| hand.is_some()));
+        assert_eq!(table.stacks().iter().sum::<u32>(), 300);
+        assert_eq!(table.button(), 2);
+    }
+    #[test]
+    fn test_rotation_keeps_the_table_rake() {
+        // Only the second game sets its own rake, the others take the table's every time they come round
+        let games = vec![
+            GameConfig::new(PokerVariant::TexasHoldem, 1, 2),
+            GameConfig::new(PokerVariant::TexasHoldem, 2, 4).with_rake(Rake::new(10)),
+            GameConfig::new(PokerVariant::TexasHoldem, 5, 10),
+        ];
+        let mut table = Table::new(GameConfig::new(PokerVariant::TexasHoldem, 1, 2).with_rake(Rake::new(5)));
+        for _ in 0..3 {
+            table.seat(Player::new(CheckFolder), 100);
+        }
+        table.set_rotation(Rotation::new(games.clone(), RotationLength::Hands(1)).unwrap());
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut rakes = Vec::new();
+        for _ in 0..4 {
+            table.play_hand(&mut rng).unwrap();
+            rakes.push(table.config().rake.clone());
+        }
+        assert_eq!(rakes, vec![Some(Rake::new(5)), Some(Rake::new(10)), Some(Rake::new(5)), Some(Rake::new(5))]);
+
+        // Without a table rake the game after the raked one takes none
+        let mut table = Table::new(GameConfig::new(PokerVariant::TexasHoldem, 1, 2));
+        for _ in 0..3 {
+            table.seat(Player::new(CheckFolder), 100);
+        }
+        table.set_rotation(Rotation::new(games, RotationLength::Hands(1)).unwrap());
+        let rakes: Vec<_> = (0..3).map(|_| {
+            table.play_hand(&mut rng).unwrap();
+            table.config().rake.clone()
+        }).collect();
+        assert_eq!(rakes, vec![None, Some(Rake::new(10)), None]);
+    }
+}
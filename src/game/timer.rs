@@ -0,0 +1,163 @@
+use std::time::Duration;
+use crate::game::clock::{Clock, SystemClock};
+
+// Time allowed for each decision, and an extra bank of time per player used up once a decision goes over it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeControl {
+    pub per_action: Duration,
+    pub time_bank: Duration,
+}
+
+// Times the decisions of the players at a table, the table asks the timer whether a decision came in time
+// and plays the default action for the player when it didn't (see LegalActions::default_action)
+// The clock starts when a player is first asked and keeps running if they have to be asked again after an illegal action
+#[derive(Debug)]
+pub struct ActionTimer {
+    control: TimeControl,
+    clock: Box<dyn Clock>,
+    banks: Vec<Duration>,                 // Time bank left for each seat
+    running: Option<(usize, Duration)>,   // Seat being timed and when its clock started
+}
+
+impl ActionTimer {
+    pub fn new(control: TimeControl) -> Self {
+        ActionTimer { control, clock: Box::new(SystemClock::new()), banks: Vec::new(), running: None }
+    }
+
+    // Same timer reading the time from another clock
+    pub fn with_clock(mut self, clock: Box<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn control(&self) -> TimeControl {
+        self.control
+    }
+
+    // Gives a new seat a full time bank
+    pub fn add_seat(&mut self) {
+        self.banks.push(self.control.time_bank);
+    }
+
+    // Forgets a seat that left, the seats after it move up one
+    pub fn remove_seat(&mut self, seat: usize) {
+        self.banks.remove(seat);
+        if let Some((timed, started)) = self.running {
+            self.running = match timed.cmp(&seat) {
+                std::cmp::Ordering::Less => Some((timed, started)),
+                std::cmp::Ordering::Equal => None,
+                std::cmp::Ordering::Greater => Some((timed - 1, started)),
+            };
+        }
+    }
+
+    // Time bank a seat has left
+    pub fn bank(&self, seat: usize) -> Duration {
+        self.banks[seat]
+    }
+
+    // Starts timing a seat's decision, unless it is already being timed
+    pub fn start(&mut self, seat: usize) {
+        if self.running.is_none_or(|(timed, _)| timed != seat) {
+            self.running = Some((seat, self.clock.elapsed()));
+        }
+    }
+
+    // Time a seat has spent on its current decision
+    pub fn used(&self, seat: usize) -> Duration {
+        match self.running {
+            Some((timed, started)) if timed == seat => self.clock.elapsed().saturating_sub(started),
+            _ => Duration::ZERO,
+        }
+    }
+
+    // Time a seat has left for its current decision, the time for the action and then its bank
+    pub fn time_left(&self, seat: usize) -> Duration {
+        (self.control.per_action + self.banks[seat]).saturating_sub(self.used(seat))
+    }
+
+    // Whether a seat has used all of its time on the current decision
+    pub fn is_out_of_time(&self, seat: usize) -> bool {
+        self.used(seat) > self.control.per_action + self.banks[seat]
+    }
+
+    // Stops timing the decision, any time over the time for the action comes out of the bank
+    pub fn stop(&mut self, seat: usize) {
+        let over = self.used(seat).saturating_sub(self.control.per_action);
+        self.banks[seat] = self.banks[seat].saturating_sub(over);
+        self.running = None;
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::clock::ManualClock;
+    use crate::game::legal::LegalActions;
+    use crate::players::action::{Action, Discards};
+
+    // 10 seconds a decision with a 30 second time bank, for two seats
+    fn timer(clock: &ManualClock) -> ActionTimer {
+        let mut timer = ActionTimer::new(TimeControl { per_action: Duration::from_secs(10), time_bank: Duration::from_secs(30) })
+            .with_clock(Box::new(clock.clone()));
+        timer.add_seat();
+        timer.add_seat();
+        timer
+    }
+
+    #[test]
+    fn test_quick_decisions_keep_the_bank() {
+        let clock = ManualClock::new();
+        let mut timer = timer(&clock);
+        timer.start(0);
+        clock.advance(Duration::from_secs(8));
+        assert_eq!(timer.time_left(0), Duration::from_secs(32));
+        assert!(!timer.is_out_of_time(0));
+        timer.stop(0);
+        assert_eq!(timer.bank(0), Duration::from_secs(30));
+    }
+    #[test]
+    fn test_slow_decisions_use_the_bank() {
+        let clock = ManualClock::new();
+        let mut timer = timer(&clock);
+        timer.start(1);
+        clock.advance(Duration::from_secs(25));
+        // Being asked again after an illegal action doesn't restart the clock
+        timer.start(1);
+        assert_eq!(timer.used(1), Duration::from_secs(25));
+        timer.stop(1);
+        assert_eq!(timer.bank(1), Duration::from_secs(15));
+        timer.start(1);
+        clock.advance(Duration::from_secs(26));
+        assert!(timer.is_out_of_time(1));
+        assert_eq!(timer.time_left(1), Duration::ZERO);
+        timer.stop(1);
+        assert_eq!(timer.bank(1), Duration::ZERO);
+        assert_eq!(timer.bank(0), Duration::from_secs(30));
+    }
+    #[test]
+    fn test_removing_a_seat_keeps_the_banks_in_order() {
+        let clock = ManualClock::new();
+        let mut timer = timer(&clock);
+        timer.add_seat();
+        timer.start(2);
+        clock.advance(Duration::from_secs(20));
+        timer.remove_seat(0);
+        assert_eq!(timer.used(1), Duration::from_secs(20));
+        timer.stop(1);
+        assert_eq!(timer.bank(1), Duration::from_secs(20));
+    }
+    #[test]
+    fn test_default_action_checks_or_folds() {
+        let mut legal = LegalActions { seat: 0, can_check: true, call: None, bet: None, raise: None, all_in: Some(100), bring_in: None, draw: None };
+        assert_eq!(legal.default_action(), Action::Check);
+        legal.can_check = false;
+        legal.call = Some(10);
+        assert_eq!(legal.default_action(), Action::Fold);
+        legal.bring_in = Some(2);
+        assert_eq!(legal.default_action(), Action::Bet(2));
+        legal.draw = Some(5);
+        assert_eq!(legal.default_action(), Action::Draw(Discards::none()));
+    }
+}
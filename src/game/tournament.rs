@@ -0,0 +1,381 @@
+use std::time::Duration;
+use crate::game::clock::{Clock, SystemClock};
+use crate::game::config::GameConfig;
+use crate::game::events::Observer;
+use crate::game::state::{HandResult, RuleError};
+use crate::game::table::Table;
+use crate::players::player::Player;
+use rand::Rng;
+use thiserror::Error;
+
+// Forced bets for one level of a tournament
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlindLevel {
+    pub small_blind: u32,
+    pub big_blind: u32,
+    pub ante: u32,
+}
+
+// How long each level lasts, a number of hands or a length of time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LevelLength {
+    Hands(u32),
+    Time(Duration),
+}
+
+// Blind levels in order, the last level lasts until the tournament is over
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlindSchedule {
+    levels: Vec<BlindLevel>,
+    length: LevelLength,
+}
+
+impl BlindSchedule {
+    pub fn new(levels: Vec<BlindLevel>, length: LevelLength) -> Result<Self, TournamentError> {
+        if levels.is_empty() {
+            return Err(TournamentError::EmptySchedule);
+        }
+        if length == LevelLength::Hands(0) || length == LevelLength::Time(Duration::ZERO) {
+            return Err(TournamentError::ZeroLevelLength);
+        }
+        Ok(BlindSchedule { levels, length })
+    }
+
+    // Index of the level after hands_played hands and elapsed time
+    pub fn level_index(&self, hands_played: u32, elapsed: Duration) -> usize {
+        let index = match self.length {
+            LevelLength::Hands(hands) => (hands_played / hands) as usize,
+            LevelLength::Time(time) => (elapsed.as_nanos() / time.as_nanos()) as usize,
+        };
+        index.min(self.levels.len() - 1)
+    }
+
+    // The level after hands_played hands and elapsed time
+    pub fn level(&self, hands_played: u32, elapsed: Duration) -> BlindLevel {
+        self.levels[self.level_index(hands_played, elapsed)]
+    }
+
+    pub fn levels(&self) -> &[BlindLevel] {
+        &self.levels
+    }
+}
+
+// Share of the prize pool paid to each place, first place first, in percent
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Payouts {
+    percentages: Vec<u32>,
+}
+
+impl Payouts {
+    // The percentages have to add up to 100
+    pub fn new(percentages: Vec<u32>) -> Result<Self, TournamentError> {
+        let total: u32 = percentages.iter().sum();
+        if total != 100 {
+            return Err(TournamentError::PayoutsNotHundred(total));
+        }
+        Ok(Payouts { percentages })
+    }
+
+    // Prize for each paid place, chips lost to rounding go to first place
+    pub fn prizes(&self, pool: u32) -> Vec<u32> {
+        let mut prizes: Vec<u32> = self.percentages.iter().map(|&percent| (pool as u64 * percent as u64 / 100) as u32).collect();
+        let leftover = pool - prizes.iter().sum::<u32>();
+        if let Some(first) = prizes.first_mut() {
+            *first += leftover;
+        }
+        prizes
+    }
+
+    // Number of places paid
+    pub fn places_paid(&self) -> usize {
+        self.percentages.iter().filter(|&&percent| percent > 0).count()
+    }
+}
+
+// Where a player finished and what they won
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Standing {
+    pub seat: usize,
+    pub place: usize, // 1 is the winner
+    pub prize: u32,
+}
+
+// A single table tournament (sit and go): everyone starts with the same chips, the blinds go up on a schedule,
+// players who lose their chips are out and the prize pool is paid by finishing place
+#[derive(Debug)]
+pub struct Tournament {
+    table: Table,
+    config: GameConfig,  // Rules of the hands, the blinds and ante are replaced by those of the current level
+    schedule: BlindSchedule,
+    payouts: Payouts,
+    clock: Box<dyn Clock>,
+    buy_in: u32,         // Goes into the prize pool
+    entry_fee: u32,      // Kept by the house on top of the buy-in, tournament pots aren't raked
+    starting_stack: u32,
+    hands_played: u32,
+    eliminated: Vec<usize>, // Seats in the order they went out, first out first
+}
+
+impl Tournament {
+    pub fn new(config: GameConfig, schedule: BlindSchedule, payouts: Payouts, buy_in: u32, starting_stack: u32) -> Self {
+        Tournament {
+            table: Table::new(config.clone()),
+            config,
+            schedule,
+            payouts,
+            clock: Box::new(SystemClock::new()),
+            buy_in,
+            entry_fee: 0,
+            starting_stack,
+            hands_played: 0,
+            eliminated: Vec::new(),
+        }
+    }
+
+    // Same tournament timed by another clock
+    pub fn with_clock(mut self, clock: Box<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    // Same tournament with an entry fee paid by each player on top of the buy-in
+    pub fn with_entry_fee(mut self, fee: u32) -> Self {
+        self.entry_fee = fee;
+        self
+    }
+
+    // Registers a player with the starting stack
+    pub fn enter(&mut self, player: Player) {
+        self.table.seat(player, self.starting_stack);
+    }
+
+    // Sends the events of every hand from now on to an observer
+    pub fn subscribe(&mut self, observer: Box<dyn Observer>) {
+        self.table.subscribe(observer);
+    }
+
+    pub fn table(&self) -> &Table {
+        &self.table
+    }
+
+    pub fn hands_played(&self) -> u32 {
+        self.hands_played
+    }
+
+    // The blinds and ante for the next hand
+    pub fn level(&self) -> BlindLevel {
+        self.schedule.level(self.hands_played, self.clock.elapsed())
+    }
+
+    // Buy-ins of everyone who entered
+    pub fn prize_pool(&self) -> u32 {
+        self.buy_in * self.table.players().len() as u32
+    }
+
+    // Entry fees the house took from everyone who entered
+    pub fn fees(&self) -> u32 {
+        self.entry_fee * self.table.players().len() as u32
+    }
+
+    // Seats in the order they were knocked out
+    pub fn eliminated(&self) -> &[usize] {
+        &self.eliminated
+    }
+
+    // The tournament is over once one player has all the chips
+    pub fn is_over(&self) -> bool {
+        self.table.players_with_chips() <= 1
+    }
+
+    // Plays one hand at the current level, then knocks out anyone who lost all their chips
+    pub fn play_hand<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Result<HandResult, TournamentError> {
+        if self.table.players().len() < 2 {
+            return Err(TournamentError::NotEnoughPlayers);
+        }
+        if self.is_over() {
+            return Err(TournamentError::Over);
+        }
+        let level = self.level();
+        self.table.set_config(GameConfig {
+            small_blind: level.small_blind,
+            big_blind: level.big_blind,
+            ante: level.ante,
+            rake: None,
+            ..self.config.clone()
+        });
+        let before = self.table.stacks().to_vec();
+        let result = self.table.play_hand(rng)?;
+        self.hands_played += 1;
+        let after = self.table.stacks().to_vec();
+        self.record_eliminations(&before, &after);
+        Ok(result)
+    }
+
+    // Plays hands until there is a winner and returns the final standings
+    pub fn run<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Result<Vec<Standing>, TournamentError> {
+        while !self.is_over() {
+            self.play_hand(rng)?;
+        }
+        Ok(self.standings())
+    }
+
+    // Players who went out in the same hand finish in order of the chips they started the hand with, more chips finish higher
+    fn record_eliminations(&mut self, before: &[u32], after: &[u32]) {
+        let mut busted: Vec<usize> = (0..after.len()).filter(|&seat| before[seat] > 0 && after[seat] == 0).collect();
+        busted.sort_by_key(|&seat| (before[seat], std::cmp::Reverse(seat)));
+        self.eliminated.extend(busted);
+    }
+
+    // Places and prizes of the players who have finished, the winner included once the tournament is over
+    pub fn standings(&self) -> Vec<Standing> {
+        let winner = (0..self.table.players().len()).find(|&seat| self.table.stacks()[seat] > 0).filter(|_| self.is_over());
+        standings(self.table.players().len(), winner, &self.eliminated, &self.payouts.prizes(self.prize_pool()))
+    }
+}
+
+// Places and prizes from the order players went out in, seat is whatever number the players are known by
+// Places are counted from the bottom so they are right before the tournament is over too
+pub(crate) fn standings(entrants: usize, winner: Option<usize>, eliminated: &[usize], prizes: &[u32]) -> Vec<Standing> {
+    let finished: Vec<usize> = winner.into_iter().chain(eliminated.iter().rev().copied()).collect();
+    let first_place = entrants - finished.len() + 1;
+    finished
+        .into_iter()
+        .enumerate()
+        .map(|(i, seat)| Standing { seat, place: first_place + i, prize: prizes.get(first_place + i - 1).copied().unwrap_or(0) })
+        .collect()
+}
+
+// Errors running a tournament
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum TournamentError {
+    #[error("The blind schedule has no levels")]
+    EmptySchedule,
+    #[error("Levels can't last zero hands or zero time")]
+    ZeroLevelLength,
+    #[error("Payouts add up to {0}%, not 100%")]
+    PayoutsNotHundred(u32),
+    #[error("A tournament needs at least two players")]
+    NotEnoughPlayers,
+    #[error("The tournament is over")]
+    Over,
+    #[error("{0}")]
+    Rule(#[from] RuleError),
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::clock::ManualClock;
+    use crate::game::variant::PokerVariant;
+    use crate::players::player::{CallStation, RandomPlayer};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn levels() -> Vec<BlindLevel> {
+        vec![
+            BlindLevel { small_blind: 10, big_blind: 20, ante: 0 },
+            BlindLevel { small_blind: 20, big_blind: 40, ante: 5 },
+            BlindLevel { small_blind: 50, big_blind: 100, ante: 10 },
+        ]
+    }
+
+    // Tournament for the given number of bots with a buy-in of 10 and levels of 5 hands,
+    // call stations in the even seats and seeded random players in the odd ones
+    fn sit_and_go(players: usize) -> Tournament {
+        let schedule = BlindSchedule::new(levels(), LevelLength::Hands(5)).unwrap();
+        let payouts = Payouts::new(vec![50, 30, 20]).unwrap();
+        let mut tournament = Tournament::new(GameConfig::new(PokerVariant::TexasHoldem, 10, 20), schedule, payouts, 10, 1500);
+        for i in 0..players {
+            let name = format!("Player {}", i + 1);
+            if i % 2 == 0 {
+                tournament.enter(Player::new(CallStation::new().with_name(&name)));
+            } else {
+                tournament.enter(Player::new(RandomPlayer::new().with_name(&name).with_seed(i as u64)));
+            }
+        }
+        tournament
+    }
+
+    #[test]
+    fn test_levels_by_hands() {
+        let schedule = BlindSchedule::new(levels(), LevelLength::Hands(5)).unwrap();
+        assert_eq!(schedule.level_index(0, Duration::ZERO), 0);
+        assert_eq!(schedule.level_index(4, Duration::ZERO), 0);
+        assert_eq!(schedule.level(5, Duration::ZERO).big_blind, 40);
+        // The last level lasts forever
+        assert_eq!(schedule.level(500, Duration::ZERO).big_blind, 100);
+    }
+    #[test]
+    fn test_levels_by_clock() {
+        let clock = ManualClock::new();
+        let schedule = BlindSchedule::new(levels(), LevelLength::Time(Duration::from_secs(600))).unwrap();
+        let tournament = sit_and_go(3).with_clock(Box::new(clock.clone()));
+        let mut tournament = Tournament { schedule, ..tournament };
+        assert_eq!(tournament.level().big_blind, 20);
+        clock.advance(Duration::from_secs(599));
+        assert_eq!(tournament.level().big_blind, 20);
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(tournament.level(), BlindLevel { small_blind: 20, big_blind: 40, ante: 5 });
+        // Hands played don't matter when levels go by time
+        tournament.hands_played = 100;
+        assert_eq!(tournament.level().big_blind, 40);
+    }
+    #[test]
+    fn test_invalid_schedule_and_payouts() {
+        assert_eq!(BlindSchedule::new(Vec::new(), LevelLength::Hands(5)), Err(TournamentError::EmptySchedule));
+        assert_eq!(BlindSchedule::new(levels(), LevelLength::Hands(0)), Err(TournamentError::ZeroLevelLength));
+        assert_eq!(Payouts::new(vec![50, 30]), Err(TournamentError::PayoutsNotHundred(80)));
+    }
+    #[test]
+    fn test_payouts() {
+        let payouts = Payouts::new(vec![50, 30, 20]).unwrap();
+        assert_eq!(payouts.prizes(100), vec![50, 30, 20]);
+        // Rounding leftovers go to the winner
+        assert_eq!(payouts.prizes(101), vec![51, 30, 20]);
+        assert_eq!(payouts.places_paid(), 3);
+    }
+    #[test]
+    fn test_eliminations_and_standings() {
+        let mut tournament = sit_and_go(4);
+        // Seats 1 and 3 bust in the same hand, seat 3 started it with fewer chips so it finishes lower
+        tournament.record_eliminations(&[1500, 800, 1500, 600], &[2200, 0, 2200, 0]);
+        assert_eq!(tournament.eliminated(), &[3, 1]);
+        assert!(!tournament.is_over());
+        let standings = tournament.standings();
+        assert_eq!(standings[0], Standing { seat: 1, place: 3, prize: 8 });
+        assert_eq!(standings[1], Standing { seat: 3, place: 4, prize: 0 });
+    }
+    #[test]
+    fn test_sit_and_go_runs_to_a_winner() {
+        let mut tournament = sit_and_go(4);
+        let mut rng = StdRng::seed_from_u64(10);
+        // The blinds go up every 5 hands
+        let mut levels = Vec::new();
+        while !tournament.is_over() {
+            levels.push(tournament.level().big_blind);
+            tournament.play_hand(&mut rng).unwrap();
+        }
+        assert!(levels.len() >= 11);
+        assert!(levels.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert_eq!(&levels[..11], &[20, 20, 20, 20, 20, 40, 40, 40, 40, 40, 100]);
+        // Everyone finishes in a different place, the winner with every chip, and the prizes are the whole pool
+        let standings = tournament.standings();
+        let places: Vec<usize> = standings.iter().map(|standing| standing.place).collect();
+        assert_eq!(places, vec![1, 2, 3, 4]);
+        assert_eq!(tournament.table().stacks()[standings[0].seat], 6000);
+        // The last one out finishes second
+        let mut finishing_order: Vec<usize> = tournament.eliminated().to_vec();
+        finishing_order.reverse();
+        assert_eq!(finishing_order, standings[1..].iter().map(|standing| standing.seat).collect::<Vec<_>>());
+        assert_eq!(standings.iter().map(|standing| standing.prize).sum::<u32>(), tournament.prize_pool());
+        // Running it again from the same seeds finishes the same way
+        assert_eq!(sit_and_go(4).run(&mut StdRng::seed_from_u64(10)).unwrap(), standings);
+    }
+    #[test]
+    fn test_entry_fee_is_not_in_the_prize_pool() {
+        let tournament = sit_and_go(4).with_entry_fee(2);
+        assert_eq!(tournament.fees(), 8);
+        assert_eq!(tournament.prize_pool(), 40);
+    }
+}
@@ -1,46 +1,272 @@
 use std::fmt;
+use std::str::FromStr;
+use rand::Rng;
+use crate::cards::card::{Card, Suit};
+use crate::cards::deck::{Deck, DeckError};
+use crate::rank::evaluator::{best_deuce_to_seven, best_hand, best_low_hand, best_omaha_hand, best_omaha_low, evaluate_small};
+use crate::rank::handrank::{Hand, LowHand};
 use thiserror::Error;
 
 // First, let's define the different poker variants
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PokerVariant {
-    Kuhn,           // 1 card
-    ThreeCard,      // 3 cards
-    FiveCard,       // 5 cards
-    TexasHoldem,    // 2 hole cards + 5 community
-    OmahaHoldem,    // 4 hole cards + 5 community
+    Kuhn,               // 1 card from a deck of J, Q, K, two players
+    Leduc,              // 1 card + 1 community from a deck of two each of J, Q, K, two players
+    ThreeCard,          // 3 cards
+    FiveCard,           // 5 cards with one draw
+    TwoSevenTripleDraw, // 5 cards with three draws, the lowest hand wins (deuce-to-seven)
+    TexasHoldem,        // 2 hole cards + 5 community
+    OmahaHoldem,        // 4 hole cards + 5 community
+    OmahaHiLo,          // Omaha with the pot split between the best high and the best eight or better low
+    SevenCardStud,      // 3 down + 4 up, no community cards
+    SevenCardStudHiLo,  // Seven card stud with the pot split between the best high and the best eight or better low
+    Razz,               // Seven card stud where the best ace-to-five low takes the whole pot
+}
+
+// How the first money goes in the pot
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ForcedBets {
+    Blinds,  // Small and big blind left of the button (flop and draw games)
+    BringIn, // Antes and a bring-in decided by the door cards (stud games)
+    Antes,   // Antes only (Kuhn and Leduc)
+}
+
+// Cards dealt before a betting round
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Street {
+    pub down: usize,  // Cards dealt face down to each player
+    pub up: usize,    // Cards dealt face up to each player
+    pub board: usize, // Community cards dealt face up in the middle
+    pub draw: bool,   // Players discard and draw replacements before the betting round
 }
 
 impl fmt::Display for PokerVariant {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let variant_name = match self {
             PokerVariant::Kuhn => "Kuhn",
+            PokerVariant::Leduc => "Leduc Holdem",
             PokerVariant::ThreeCard => "Three Card",
             PokerVariant::FiveCard => "Five Card",
+            PokerVariant::TwoSevenTripleDraw => "2-7 Triple Draw",
             PokerVariant::TexasHoldem => "Texas Holdem",
             PokerVariant::OmahaHoldem => "Omaha Holdem",
+            PokerVariant::OmahaHiLo => "Omaha Hi/Lo",
+            PokerVariant::SevenCardStud => "Seven Card Stud",
+            PokerVariant::SevenCardStudHiLo => "Seven Card Stud Hi/Lo",
+            PokerVariant::Razz => "Razz",
         };
         write!(f, "{}", variant_name)
     }
 }
 
+// Reads a game from its name, case, spaces and punctuation are ignored so "Texas Hold'em", "holdem" and "texas-holdem" all work
+// Hi/lo games can also be written with an 8, e.g. "omaha8" or "stud8"
+impl FromStr for PokerVariant {
+    type Err = VariantError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name: String = s.chars().filter(|c| c.is_ascii_alphanumeric()).collect::<String>().to_lowercase();
+        match name.as_str() {
+            "kuhn" => Ok(PokerVariant::Kuhn),
+            "leduc" | "leducholdem" => Ok(PokerVariant::Leduc),
+            "threecard" => Ok(PokerVariant::ThreeCard),
+            "fivecard" | "fivecarddraw" => Ok(PokerVariant::FiveCard),
+            "27tripledraw" | "tripledraw" => Ok(PokerVariant::TwoSevenTripleDraw),
+            "holdem" | "texasholdem" => Ok(PokerVariant::TexasHoldem),
+            "omaha" | "omahaholdem" => Ok(PokerVariant::OmahaHoldem),
+            "omahahilo" | "omaha8" => Ok(PokerVariant::OmahaHiLo),
+            "stud" | "sevencardstud" => Ok(PokerVariant::SevenCardStud),
+            "studhilo" | "sevencardstudhilo" | "stud8" => Ok(PokerVariant::SevenCardStudHiLo),
+            "razz" => Ok(PokerVariant::Razz),
+            _ => Err(VariantError::Unknown(s.to_string())),
+        }
+    }
+}
+
+// Errors for games that don't exist
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum VariantError {
+    #[error("Unknown game {0}")]
+    Unknown(String),
+}
+
 impl PokerVariant {
     // Get the number of hole cards for each variant
     pub fn hole_cards(&self) -> usize {
         match self {
-            PokerVariant::Kuhn => 1,
+            PokerVariant::Kuhn | PokerVariant::Leduc => 1,
             PokerVariant::ThreeCard => 3,
-            PokerVariant::FiveCard => 5,
+            PokerVariant::FiveCard | PokerVariant::TwoSevenTripleDraw => 5,
             PokerVariant::TexasHoldem => 2,
-            PokerVariant::OmahaHoldem => 4,
+            PokerVariant::OmahaHoldem | PokerVariant::OmahaHiLo => 4,
+            PokerVariant::SevenCardStud | PokerVariant::SevenCardStudHiLo | PokerVariant::Razz => 7,
         }
     }
 
     // Get the number of community cards (if any)
     pub fn community_cards(&self) -> usize {
         match self {
-            PokerVariant::TexasHoldem | PokerVariant::OmahaHoldem => 5,
+            PokerVariant::TexasHoldem | PokerVariant::OmahaHoldem | PokerVariant::OmahaHiLo => 5,
+            PokerVariant::Leduc => 1,
             _ => 0,
         }
     }
+
+    // Most players that can be dealt in: every hand and the board have to come out of one deck
+    // Draw games reshuffle the discards when the deck runs out, so only the first deal limits them,
+    // and in stud with eight players the last card is dealt once in the middle for everyone
+    pub fn max_players(&self) -> usize {
+        match self {
+            PokerVariant::Kuhn | PokerVariant::Leduc => 2,
+            PokerVariant::TexasHoldem => 23,
+            PokerVariant::OmahaHoldem | PokerVariant::OmahaHiLo => 10,
+            PokerVariant::SevenCardStud | PokerVariant::SevenCardStudHiLo | PokerVariant::Razz => 8,
+            PokerVariant::ThreeCard | PokerVariant::FiveCard | PokerVariant::TwoSevenTripleDraw => 52 / self.hole_cards(),
+        }
+    }
+
+    // Deck the variant is played with shuffled by rng, the standard 52 cards or the few cards of the research games
+    pub fn shuffled_deck<R: Rng + ?Sized>(&self, rng: &mut R) -> Result<Deck, DeckError> {
+        let (suits, ranks): (&[Suit], &[u8]) = match self {
+            PokerVariant::Kuhn => (&[Suit::Spades], &[11, 12, 13]),
+            PokerVariant::Leduc => (&[Suit::Hearts, Suit::Spades], &[11, 12, 13]),
+            _ => {
+                let mut deck = Deck::new()?;
+                deck.initialize_and_shuffle_with(rng)?;
+                return Ok(deck);
+            }
+        };
+        let mut cards = Vec::with_capacity(suits.len() * ranks.len());
+        for &suit in suits {
+            for &rank in ranks {
+                cards.push(Card::new(suit, rank)?);
+            }
+        }
+        let mut deck = Deck::from_cards(cards);
+        deck.shuffle_with(rng);
+        Ok(deck)
+    }
+
+    // What is dealt before each betting round, empty for variants the game engine can't play yet
+    pub fn streets(&self) -> &'static [Street] {
+        const HOLDEM: [Street; 4] = [
+            Street { down: 2, up: 0, board: 0, draw: false }, // Preflop
+            Street { down: 0, up: 0, board: 3, draw: false }, // Flop
+            Street { down: 0, up: 0, board: 1, draw: false }, // Turn
+            Street { down: 0, up: 0, board: 1, draw: false }, // River
+        ];
+        const OMAHA: [Street; 4] = [
+            Street { down: 4, up: 0, board: 0, draw: false },
+            Street { down: 0, up: 0, board: 3, draw: false },
+            Street { down: 0, up: 0, board: 1, draw: false },
+            Street { down: 0, up: 0, board: 1, draw: false },
+        ];
+        const STUD: [Street; 5] = [
+            Street { down: 2, up: 1, board: 0, draw: false }, // Third street, the up card is the door card
+            Street { down: 0, up: 1, board: 0, draw: false }, // Fourth street
+            Street { down: 0, up: 1, board: 0, draw: false }, // Fifth street
+            Street { down: 0, up: 1, board: 0, draw: false }, // Sixth street
+            Street { down: 1, up: 0, board: 0, draw: false }, // Seventh street (the river)
+        ];
+        const FIVE_CARD: [Street; 2] = [
+            Street { down: 5, up: 0, board: 0, draw: false },
+            Street { down: 0, up: 0, board: 0, draw: true },
+        ];
+        const TRIPLE_DRAW: [Street; 4] = [
+            Street { down: 5, up: 0, board: 0, draw: false },
+            Street { down: 0, up: 0, board: 0, draw: true },
+            Street { down: 0, up: 0, board: 0, draw: true },
+            Street { down: 0, up: 0, board: 0, draw: true },
+        ];
+        const KUHN: [Street; 1] = [Street { down: 1, up: 0, board: 0, draw: false }];
+        const LEDUC: [Street; 2] = [
+            Street { down: 1, up: 0, board: 0, draw: false },
+            Street { down: 0, up: 0, board: 1, draw: false },
+        ];
+        match self {
+            PokerVariant::Kuhn => &KUHN,
+            PokerVariant::Leduc => &LEDUC,
+            PokerVariant::FiveCard => &FIVE_CARD,
+            PokerVariant::TwoSevenTripleDraw => &TRIPLE_DRAW,
+            PokerVariant::TexasHoldem => &HOLDEM,
+            PokerVariant::OmahaHoldem | PokerVariant::OmahaHiLo => &OMAHA,
+            PokerVariant::SevenCardStud | PokerVariant::SevenCardStudHiLo | PokerVariant::Razz => &STUD,
+            _ => &[],
+        }
+    }
+
+    // Stud games have no blinds or board, the up cards decide who brings it in and who acts first
+    pub fn is_stud(&self) -> bool {
+        matches!(self, PokerVariant::SevenCardStud | PokerVariant::SevenCardStudHiLo | PokerVariant::Razz)
+    }
+
+    // Whether half the pot goes to the best eight or better low
+    pub fn is_hi_lo(&self) -> bool {
+        matches!(self, PokerVariant::SevenCardStudHiLo | PokerVariant::OmahaHiLo)
+    }
+
+    // Whether the lowest hand wins the whole pot, in razz the highest door card brings it in and the lowest hand showing acts first
+    pub fn is_lowball(&self) -> bool {
+        matches!(self, PokerVariant::TwoSevenTripleDraw | PokerVariant::Razz)
+    }
+
+    // Whether hands are made with exactly two hole cards and three from the board
+    pub fn is_omaha(&self) -> bool {
+        matches!(self, PokerVariant::OmahaHoldem | PokerVariant::OmahaHiLo)
+    }
+
+    // Whether there are community cards, needed for bomb pots and games with more than one board
+    pub fn has_board(&self) -> bool {
+        self.streets().iter().any(|street| street.board > 0)
+    }
+
+    pub fn forced_bets(&self) -> ForcedBets {
+        match self {
+            _ if self.is_stud() => ForcedBets::BringIn,
+            PokerVariant::Kuhn | PokerVariant::Leduc => ForcedBets::Antes,
+            _ => ForcedBets::Blinds,
+        }
+    }
+
+    // First street on which the big bet is used in limit games
+    pub fn big_bet_street(&self) -> usize {
+        match self {
+            PokerVariant::FiveCard | PokerVariant::Leduc => 1, // After the draw, or once the Leduc board card is out
+            _ => 2,                      // The turn in flop games, fifth street in stud, after the second draw
+        }
+    }
+
+    // First street that counts as seeing the flop for no flop no drop rake: the flop in board games, fourth street in stud
+    // and the first draw in draw games, None when there is only one betting round
+    pub fn flop_street(&self) -> Option<usize> {
+        let streets = self.streets();
+        if self.has_board() {
+            streets.iter().position(|street| street.board > 0)
+        } else if self.is_stud() {
+            Some(1)
+        } else {
+            streets.iter().position(|street| street.draw)
+        }
+    }
+
+    // Best hand a player can make from their own cards and the board, None in lowball games where there is no high hand
+    pub fn best_hand(&self, cards: &[Card], board: &[Card]) -> Option<Hand> {
+        match self {
+            PokerVariant::OmahaHoldem | PokerVariant::OmahaHiLo => best_omaha_hand(cards, board),
+            PokerVariant::TwoSevenTripleDraw | PokerVariant::Razz => None,
+            PokerVariant::Kuhn | PokerVariant::Leduc => evaluate_small(&[cards, board].concat()),
+            _ => best_hand(&[cards, board].concat()),
+        }
+    }
+
+    // Best qualifying low for hi/lo and lowball games, None if the player has no low or the game has no low half
+    pub fn best_low(&self, cards: &[Card], board: &[Card]) -> Option<LowHand> {
+        match self {
+            PokerVariant::SevenCardStudHiLo => best_low_hand(&[cards, board].concat(), Some(8)),
+            PokerVariant::OmahaHiLo => best_omaha_low(cards, board, Some(8)),
+            PokerVariant::Razz => best_low_hand(&[cards, board].concat(), None),
+            PokerVariant::TwoSevenTripleDraw => best_deuce_to_seven(&[cards, board].concat()),
+            _ => None,
+        }
+    }
 }
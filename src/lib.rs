@@ -1,2 +1,6 @@
 pub mod cards;
 pub mod players;
+pub mod game;
+pub mod rank;
+#[cfg(test)]
+mod test_util;
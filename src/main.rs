@@ -1,90 +1,155 @@
-use std::io;
-use std::collections::VecDeque;
-use poker::players::player::{PlayerType, HumanPlayer};
-use poker::players::base::PlayerAction;
-use poker::players::action::Action;
+use std::time::Duration;
+use poker::players::player::{prompt, Player};
+use poker::game::config::GameConfig;
+use poker::game::events::ConsoleObserver;
+use poker::game::rotation::Rotation;
+use poker::game::session::{JoinOption, Session};
+use poker::game::setup::TableSetup;
+use poker::players::registry::AgentRegistry;
+use poker::game::timer::{ActionTimer, TimeControl};
+use poker::game::variant::PokerVariant;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
 
 fn main() {
-    println!("Hello, world! Enter the number of players: ");
-    let mut input = String::new();
-    io::stdin()
-        .read_line(&mut input)
-        .expect("Failed to read line");
-    
-    
-    let num_players = input.trim().parse::<u32>().expect("Please enter a valid number!");
+    // A table file given on the command line sets the game up, e.g. cargo run -- table.toml, otherwise the questions below do
+    // Each --seat puts an agent in the next seat, e.g. --seat human:Alice --seat ai, replacing the file's players in order
+    let mut path = None;
+    let mut specs = Vec::new();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--seat" => specs.push(args.next().unwrap_or_else(|| fail("--seat needs an agent, e.g. --seat human"))),
+            _ => path = Some(arg),
+        }
+    }
+    let registry = AgentRegistry::new();
+    let (mut session, mut rng) = match path {
+        Some(path) => {
+            let setup = TableSetup::from_file(&path)
+                .and_then(|setup| setup.with_players(&specs))
+                .and_then(|setup| Ok((setup.session(&registry)?, setup.rng())));
+            setup.unwrap_or_else(|e| fail(e))
+        }
+        None => (ask_session(&registry, &specs), StdRng::from_entropy()),
+    };
+    session.subscribe(Box::new(ConsoleObserver::new()));
+
+    // Main game loop, one hand at a time until there aren't enough players or nobody wants another hand
+    while session.table().players_dealt_in() > 1 {
+        if let Err(e) = session.play_hand(&mut rng) {
+            println!("{}", e);
+            break;
+        }
+        for (player, chips) in session.table().players().iter().zip(session.table().stacks()) {
+            println!("{} has {}", player.get_name(), chips);
+        }
+        // Players who lost everything can buy back in or leave
+        let busted: Vec<usize> = (0..session.ledger().len()).filter(|&player| session.ledger()[player].seated && session.ledger()[player].stack == 0).collect();
+        for player in busted {
+            let answer = prompt(&format!("{} is out of chips, enter a rebuy or nothing to leave: ", session.ledger()[player].name));
+            let rebuy = answer.parse::<u32>().ok().map(|chips| session.rebuy(player, chips));
+            if let Some(Err(e)) = &rebuy {
+                println!("{}", e);
+            }
+            if !matches!(rebuy, Some(Ok(()))) {
+                session.leave(player).expect("Player is seated");
+            }
+        }
+        if prompt("Play another hand? (y/n): ").to_lowercase() != "y" {
+            break;
+        }
+    }
+
+    // Everyone's result for the session
+    for entry in session.ledger() {
+        println!("{}: bought in for {}, {} {}", entry.name, entry.bought_in, if entry.net() >= 0 { "up" } else { "down" }, entry.net().abs());
+    }
+}
+
+// Prints the error and stops
+fn fail(error: impl std::fmt::Display) -> ! {
+    println!("{}", error);
+    std::process::exit(1);
+}
+
+// Sets up a cash game by asking for the players, the game, the buy-in limits and the clock
+// Players given as agent specs are seated in order, otherwise everyone playing is asked for their name
+// Every question goes through the same keyboard as the human players' decisions, see Keyboard
+fn ask_session(registry: &AgentRegistry, specs: &[String]) -> Session {
+    let mut players = Vec::new();
+    for (i, spec) in specs.iter().enumerate() {
+        players.push(registry.create(spec, &format!("Player {}", i + 1)).unwrap_or_else(|e| fail(e)));
+    }
+    let num_players = if players.is_empty() {
+        prompt("Hello, world! Enter the number of players: ").parse::<usize>().expect("Please enter a valid number!")
+    } else {
+        players.len()
+    };
 
 
     // Print the number of players
-    println!("Let initialize a {} player game of Texas Hold'em!", num_players);
+    println!("Let initialize a {} player game!", num_players);
 
 
-    // Create a vector of players
-    let mut players: Vec<PlayerType> = Vec::new();
-    for i in 0..num_players {
-        input.clear();
-        // Get the name of the player
-        println!("Enter the name of player {}: ", i + 1);
-        io::stdin().read_line(&mut input).expect("Failed to read line");
-        let name = input.trim().to_string(); // Convert the input to a string so it isn't a reference
+    // Kuhn poker and Leduc hold'em have fixed antes and bets, hold'em needs the size of the blinds
+    // and the mixed games (HORSE in order or picked by the dealer) the limits
+    let game = prompt("Enter the game to play (holdem, kuhn, leduc, horse or dealers choice): ").to_lowercase();
+    let mut rotation = None;
+    let config = if game == "horse" || game.starts_with("dealer") {
+        let bets: Vec<u32> = prompt("Enter the small bet and the big bet: ").split_whitespace().map(|number| number.parse().expect("Please enter a valid number!")).collect();
+        let [small_bet, big_bet] = bets[..] else { panic!("Please enter two numbers!") };
+        let horse = Rotation::horse(small_bet, big_bet);
+        let horse = if game == "horse" { horse } else { horse.with_dealers_choice() };
+        let config = horse.games()[0].clone();
+        rotation = Some(horse);
+        config
+    } else if game == "kuhn" {
+        GameConfig::kuhn()
+    } else if game == "leduc" {
+        GameConfig::leduc()
+    } else {
+        // The big blind is also the smallest bet allowed
+        let small_blind = prompt("Enter the small blind: ").parse::<u32>().expect("Please enter a valid number!");
+        let big_blind = prompt("Enter the big blind: ").parse::<u32>().expect("Please enter a valid number!");
+        GameConfig::new(PokerVariant::TexasHoldem, small_blind, big_blind)
+    };
 
-        // Get the buy in for the player
-        input.clear();
-        let chips: u32;
-        println!("Enter the buy in for {}: ", name);
-        io::stdin().read_line(&mut input).expect("Failed to read line");
-        chips = input.trim().parse::<u32>().expect("Please enter a valid number!");
+    // Cash game limits, every buy-in, rebuy and top up has to stay within them
+    let min_buy_in = prompt("Enter the minimum buy in: ").parse::<u32>().expect("Please enter a valid number!");
+    let max_buy_in = prompt("Enter the maximum buy in: ").parse::<u32>().expect("Please enter a valid number!");
+    let mut session = Session::new(config, min_buy_in, max_buy_in).expect("Invalid buy in limits");
+    if let Some(rotation) = rotation {
+        session.set_rotation(rotation);
+    }
 
-        // Create a new human player
-        let player = PlayerType::new_human_player(name.trim().to_string(), chips);
-        players.push(player);
+    // Optional clock on every decision, a player who takes too long checks or folds
+    let seconds: Vec<u64> = prompt("Enter the seconds to act and the seconds in each time bank, or nothing for no time limit: ").split_whitespace().filter_map(|number| number.parse().ok()).collect();
+    if let [per_action, time_bank] = seconds[..] {
+        session.set_timer(ActionTimer::new(TimeControl { per_action: Duration::from_secs(per_action), time_bank: Duration::from_secs(time_bank) }));
     }
 
-    let mut queue = VecDeque::from(players);
-    let mut num_players_in_round = queue.len();
-    // Initialize the current bet
-    let mut current_bet = 0;
-    // Flag to check if the round is complete
-    let mut round_complete = false;
-    // Main game loop
-    while !round_complete {
-        if let Some(mut player) = queue.pop_front() {
+    // Everyone else playing is at the keyboard
+    for i in players.len()..num_players {
+        // Get the name of the player
+        let name = prompt(&format!("Enter the name of player {}: ", i + 1));
+        players.push(Player::new_human_player(name));
+    }
 
-            if player.get_chips() == 0 {
-                round_complete = true;
-            }
+    // Sit everyone down, the table deals the hands and the game state applies the rules
+    for player in players {
+        let name = player.get_name().to_string();
 
-            let action = player.get_action::<HumanPlayer>(current_bet);
-            match action.action() {
-                Action::Check => {
-                    println!("{} checks", player.get_name());
-                    queue.push_back(player);
-                }
-                Action::Fold => {
-                    println!("{} folds", player.get_name());
-                }
-                Action::Call => {
-                    println!("{} calls for {}", player.get_name(), current_bet);
-                    queue.push_back(player);
-                }
-                Action::Raise(amount) => {
-                    current_bet = amount;
-                    println!("{} raises from {} to {}", player.get_name(), amount - current_bet, amount);
-                    queue.push_back(player);
-                }
-                Action::AllIn(amount) => {
-                    current_bet = amount;
-                    println!("{} goes all in to {}", player.get_name(), current_bet);
-                    queue.push_back(player);
-                }
-            }
-            num_players_in_round -= 1;
-            if num_players_in_round == 0 {
-                round_complete = true;
+        // Get the buy in for the player, asking again if it is outside the limits
+        let chips = loop {
+            let chips = prompt(&format!("Enter the buy in for {} ({} to {}): ", name, min_buy_in, max_buy_in)).parse::<u32>().expect("Please enter a valid number!");
+            if (min_buy_in..=max_buy_in).contains(&chips) {
+                break chips;
             }
-        }
+            println!("The buy in has to be from {} to {}", min_buy_in, max_buy_in);
+        };
+        session.join(player, chips, JoinOption::Post).expect("Buy in is within the limits");
     }
 
-
-
-}
\ No newline at end of file
+    session
+}
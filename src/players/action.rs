@@ -1,19 +1,147 @@
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
 #[derive(PartialEq, Eq, Copy, Clone, Debug)]
 pub enum Action {
     Check,
     Fold,
     Call,
-    Raise(u32), // Always positive since it is unsigned
-    AllIn(u32),
+    Bet(u32),   // Opening bet when nobody has bet yet, amount is the player's total for the round
+    Raise(u32), // Raise over an existing bet, amount is what the player's total is raised to, always positive since it is unsigned
+    AllIn(u32), // Amount is the player's total for the round once all their chips are in
+    Draw(Discards), // Throws away some cards in a draw game and gets new ones, no discards is standing pat
+}
+
+// The cards a player throws away in a draw, by their position in the player's hand (0 is the first card)
+// Kept as a bit set so actions stay Copy, bit i is set when the card at position i is discarded
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Default)]
+pub struct Discards(u8);
+
+impl Discards {
+    // Standing pat, keeping every card
+    pub fn none() -> Self {
+        Discards(0)
+    }
+
+    // Discards the cards at the given positions, positions past the eighth card are ignored
+    pub fn from_positions(positions: &[usize]) -> Self {
+        Discards(positions.iter().filter(|&&position| position < 8).fold(0, |bits, &position| bits | 1 << position))
+    }
+
+    // Discards the first count cards, how another player's draw is shown when only the number of cards is seen
+    pub fn first(count: usize) -> Self {
+        Discards((1u16 << count.min(8)).wrapping_sub(1) as u8)
+    }
+
+    // Whether the card at position is discarded
+    pub fn contains(&self, position: usize) -> bool {
+        position < 8 && self.0 >> position & 1 == 1
+    }
+
+    // Positions of the discarded cards, lowest first
+    pub fn positions(&self) -> Vec<usize> {
+        (0..8).filter(|&position| self.contains(position)).collect()
+    }
+
+    // Number of cards discarded
+    pub fn count(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    // Every way of discarding from a hand of the given size, from standing pat to throwing away every card
+    pub fn all(hand_size: usize) -> Vec<Discards> {
+        (0..1u16 << hand_size.min(8)).map(|bits| Discards(bits as u8)).collect()
+    }
 }
 
 impl Action {
-    // Returns the amount of chips in a raise
+    // Returns the amount of chips in a bet or raise
     pub fn amount(&self) -> u32 {
         match self {
+            Action::Bet(amount) => *amount,
             Action::Raise(amount) => *amount,
             Action::AllIn(amount) => *amount,
             _ => 0,
         }
     }
 }
+
+// Describes the action the way it is announced at the table, "raises to 40"
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Action::Check => write!(f, "checks"),
+            Action::Fold => write!(f, "folds"),
+            Action::Call => write!(f, "calls"),
+            Action::Bet(amount) => write!(f, "bets {}", amount),
+            Action::Raise(amount) => write!(f, "raises to {}", amount),
+            Action::AllIn(amount) => write!(f, "goes all in to {}", amount),
+            Action::Draw(discards) if discards.count() == 0 => write!(f, "stands pat"),
+            Action::Draw(discards) => write!(f, "draws {}", discards.count()),
+        }
+    }
+}
+
+// Reads an action written as a word and its amount, e.g. "check", "bet 6", "raise 12", "allin 100",
+// or for a draw "draw" and the positions of the cards thrown away, "draw 0 3 4", and "pat" to stand pat
+impl FromStr for Action {
+    type Err = ActionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lowered = s.to_lowercase();
+        let mut words = lowered.split_whitespace();
+        let invalid = || ActionError::Invalid(s.trim().to_string());
+        let word = words.next().ok_or_else(invalid)?;
+        let numbers: Vec<u32> = words.map(|word| word.parse()).collect::<Result<_, _>>().map_err(|_| invalid())?;
+        let amount = || match numbers[..] {
+            [amount] => Ok(amount),
+            _ => Err(invalid()),
+        };
+        match word {
+            "check" | "fold" | "call" | "pat" if !numbers.is_empty() => Err(invalid()),
+            "check" => Ok(Action::Check),
+            "fold" => Ok(Action::Fold),
+            "call" => Ok(Action::Call),
+            "pat" => Ok(Action::Draw(Discards::none())),
+            "bet" => Ok(Action::Bet(amount()?)),
+            "raise" => Ok(Action::Raise(amount()?)),
+            "allin" => Ok(Action::AllIn(amount()?)),
+            "draw" => {
+                let positions: Vec<usize> = numbers.iter().map(|&position| position as usize).collect();
+                if positions.iter().any(|&position| position >= 8) {
+                    return Err(invalid());
+                }
+                Ok(Action::Draw(Discards::from_positions(&positions)))
+            }
+            _ => Err(invalid()),
+        }
+    }
+}
+
+// Errors for text that isn't an action
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ActionError {
+    #[error("Invalid action {0}, expected e.g. check, fold, call, bet 6, raise 12, allin 100 or draw 0 3")]
+    Invalid(String),
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_actions_from_text() {
+        assert_eq!("Check".parse(), Ok(Action::Check));
+        assert_eq!(" bet 6 ".parse(), Ok(Action::Bet(6)));
+        assert_eq!("raise 3".parse(), Ok(Action::Raise(3)));
+        assert_eq!("allin 100".parse(), Ok(Action::AllIn(100)));
+        assert_eq!("draw 0 4".parse(), Ok(Action::Draw(Discards::from_positions(&[0, 4]))));
+        assert_eq!("pat".parse(), Ok(Action::Draw(Discards::none())));
+        assert!("raise".parse::<Action>().is_err());
+        assert!("call 5".parse::<Action>().is_err());
+        assert!("draw 8".parse::<Action>().is_err());
+        assert_eq!("shove 5".parse::<Action>(), Err(ActionError::Invalid(String::from("shove 5"))));
+    }
+}
@@ -1,31 +1,305 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use crate::cards::card::Card;
-use crate::players::action::Action;
-use crate::players::base::{PlayerFunctions, PlayerAction, PlayerError};
+use crate::game::equity::{estimate_equity, ranges_from_betting};
+use crate::game::legal::LegalActions;
+use crate::game::observation::Observation;
+use crate::game::variant::PokerVariant;
+use crate::players::action::{Action, Discards};
+use crate::players::base::{Agent, Decision};
 
+// How the AI player plays, both from 0 to 1
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Style {
+    pub tightness: f64,  // 0 plays most hands and calls down light, 1 only plays strong hands
+    pub aggression: f64, // 0 checks and calls, 1 bets and raises with more hands, bigger, and bluffs more
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Style { tightness: 0.5, aggression: 0.5 }
+    }
+}
+
+// Rule based player, a baseline opponent and a benchmark for the solvers
+// Hold'em preflop it plays from a chart of starting hand tiers, everywhere else it estimates its equity against the hands
+// the other players are likely to hold and bets for value when it is ahead, calls when the pot odds are good enough and folds otherwise
+// That includes the first betting round of the other games: Omaha before the flop, third street in stud (bringing it in
+// unless its equity is worth completing) and the round before the first draw all go by equity against the pot odds
 #[derive(Debug)]
 pub struct AIPlayer {
+    name: String,
+    style: Style,
+    samples: usize, // Deals to estimate the equity from
+    rng: StdRng,
 }
 
-impl PlayerFunctions for AIPlayer {
-    fn get_action(&mut self, current_bet: u32) -> PlayerAction {
-        todo!()
+impl AIPlayer {
+    pub fn new() -> Self {
+        AIPlayer { name: String::from("AI Player"), style: Style::default(), samples: 300, rng: StdRng::from_entropy() }
+    }
+
+    // Same player under another name
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = name.to_string();
+        self
+    }
+
+    // Same player with another style, clamped between 0 and 1
+    pub fn with_style(mut self, style: Style) -> Self {
+        self.style = Style { tightness: style.tightness.clamp(0.0, 1.0), aggression: style.aggression.clamp(0.0, 1.0) };
+        self
+    }
+
+    // Same player estimating its equity from the given number of deals
+    pub fn with_samples(mut self, samples: usize) -> Self {
+        self.samples = samples.max(1);
+        self
     }
-    fn receive_card(&mut self, _card: Card) -> Result<(), PlayerError> {
-        todo!()
+
+    // Same player with its random choices made from a seed, so it always plays a hand the same way
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+
+    pub fn style(&self) -> Style {
+        self.style
+    }
+
+    // Hold'em before the flop, from the tier of the hand in the chart: opens the best tiers with a raise, calls with a tier
+    // or two more when playing loose and folds the rest
+    // Facing a raise it calls a tier tighter than it opens, reraises only the best tiers as it gets more aggressive,
+    // and only calls off a big part of the stack with the first tier
+    fn preflop(&mut self, observation: &Observation, legal: &LegalActions) -> Action {
+        let [first, second] = observation.hole_cards[..] else {
+            return legal.default_action();
+        };
+        let tier = preflop_tier(first, second) as f64;
+        let Style { tightness, aggression } = self.style;
+        let open = 8.0 - 5.0 * tightness; // Tiers 1 to 8 (the best 40% of the hands) when loose, 1 to 3 (the best 7%) when tight
+        let current_bet = observation.round.current_bet();
+        let to_call = observation.to_call();
+        let stack = observation.stacks[observation.seat];
+        // Calls of more than a third of the stack are close to all in and only made with the best hands
+        let committing = to_call * 3 > stack;
+        if current_bet <= observation.config.big_blind {
+            if tier <= open {
+                return raise_to(legal, 3 * observation.config.big_blind + observation.pot);
+            }
+            if legal.can_check {
+                return Action::Check;
+            }
+            return if tier <= open + 2.0 * (1.0 - tightness) { Action::Call } else { Action::Fold };
+        }
+        if tier <= 2.0 * aggression {
+            return raise_to(legal, 3 * current_bet);
+        }
+        if tier <= if committing { 1.0 } else { open - 1.0 } {
+            return Action::Call;
+        }
+        if legal.can_check { Action::Check } else { Action::Fold }
+    }
+
+    // Compares the equity against the likely hands of the others with the pot odds
+    fn postflop(&mut self, observation: &Observation, legal: &LegalActions) -> Action {
+        let Style { tightness, aggression } = self.style;
+        let equity = estimate_equity(observation, &ranges_from_betting(observation), self.samples, &mut self.rng);
+        let to_call = observation.to_call();
+        let pot = observation.pot;
+        let current_bet = observation.round.current_bet();
+        // Equity needed to bet for value, lower for aggressive players
+        let value = 0.65 - 0.2 * aggression;
+        // Bets are half the pot for passive players up to the whole pot for aggressive ones
+        let size = pot as f64 * (0.5 + 0.5 * aggression);
+        if let Some(bring_in) = legal.bring_in {
+            return if equity >= value { raise_to(legal, legal.bet.map_or(bring_in, |range| range.min)) } else { Action::Bet(bring_in) };
+        }
+        if to_call == 0 {
+            // Aggressive players bluff now and then
+            if equity >= value || self.rng.gen_bool(0.15 * aggression) {
+                return raise_to(legal, current_bet + size as u32);
+            }
+            return if legal.can_check { Action::Check } else { legal.default_action() };
+        }
+        if equity >= value + 0.1 {
+            return raise_to(legal, current_bet + to_call + size as u32);
+        }
+        // Needs the pot odds plus a margin that grows with tightness
+        let pot_odds = to_call as f64 / (pot + to_call) as f64;
+        if equity >= pot_odds + 0.1 * tightness {
+            Action::Call
+        } else {
+            Action::Fold
+        }
+    }
+}
+
+impl Default for AIPlayer {
+    fn default() -> Self {
+        AIPlayer::new()
     }
-    fn call(&mut self, _current_bet: u32) -> Action {
-        todo!()
+}
+
+impl Agent for AIPlayer {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn decide(&mut self, observation: &Observation, legal: &LegalActions) -> Decision {
+        let action = if legal.draw.is_some() {
+            Action::Draw(discards(observation.config.variant, &observation.hole_cards))
+        } else if observation.config.variant == PokerVariant::TexasHoldem && observation.street == 0 {
+            self.preflop(observation, legal)
+        } else {
+            self.postflop(observation, legal)
+        };
+        Decision::Act(action)
+    }
+
+    // Running it more than once only lowers the variance, so it always agrees
+    fn agree_to_runouts(&mut self, _times: usize) -> bool {
+        true
+    }
+}
+
+// Hold'em starting hands in tiers from 1 (aces to jacks and ace king suited) to 9 (the hands never worth playing),
+// rows and columns go from ace down to deuce, pairs on the diagonal, suited hands above it and offsuit hands below it
+const PREFLOP_TIERS: [&[u8; 13]; 13] = [
+    b"1122355555555", // A
+    b"2123467777777", // K
+    b"3413457999999", // Q
+    b"4551346899999", // J
+    b"6665245799999", // T
+    b"8887734589999", // 9
+    b"9998874568999", // 8
+    b"9999998556899", // 7
+    b"9999999865799", // 6
+    b"9999999986679", // 5
+    b"9999999998778", // 4
+    b"9999999999978", // 3
+    b"9999999999997", // 2
+];
+
+// Tier of a hold'em starting hand in the chart
+pub(crate) fn preflop_tier(first: Card, second: Card) -> u8 {
+    let (high, low) = (first.rank().max(second.rank()), first.rank().min(second.rank()));
+    let (high, low) = (14 - high as usize, 14 - low as usize);
+    let (row, column) = if first.suit() == second.suit() { (high, low) } else { (low, high) };
+    PREFLOP_TIERS[row][column] - b'0'
+}
+
+// Bets or raises the total for the round to about amount, as close as the legal sizes allow,
+// all in when it would be most of the stack, and calls or checks when betting isn't open to the player
+fn raise_to(legal: &LegalActions, amount: u32) -> Action {
+    let Some(range) = legal.bet.or(legal.raise) else {
+        return match (legal.call, legal.all_in) {
+            (Some(_), _) => Action::Call,
+            (None, Some(total)) => Action::AllIn(total), // Less than a full bet left
+            (None, None) => legal.default_action(),
+        };
+    };
+    let amount = amount.clamp(range.min, range.max);
+    if legal.all_in.is_some_and(|total| amount * 4 >= total * 3) {
+        return Action::AllIn(legal.all_in.unwrap_or(amount));
+    }
+    if legal.bet.is_some() { Action::Bet(amount) } else { Action::Raise(amount) }
+}
+
+// Cards to throw away in a draw: in lowball every pair and card above an eight,
+// otherwise everything but pairs and better, or a four card flush, or the highest card when there is nothing else
+pub(crate) fn discards(variant: PokerVariant, hand: &[Card]) -> Discards {
+    let count = |card: &Card| hand.iter().filter(|other| other.rank() == card.rank()).count();
+    let mut thrown: Vec<usize> = Vec::new();
+    if variant.is_lowball() {
+        for (position, card) in hand.iter().enumerate() {
+            let paired = hand[..position].iter().any(|other| other.rank() == card.rank());
+            if paired || card.rank() > 8 {
+                thrown.push(position);
+            }
+        }
+        return Discards::from_positions(&thrown);
+    }
+    if let Some(suit) = hand.iter().map(|card| card.suit()).find(|&suit| hand.iter().filter(|card| card.suit() == suit).count() == 4) {
+        thrown = (0..hand.len()).filter(|&position| hand[position].suit() != suit).collect();
+    } else if hand.iter().any(|card| count(card) >= 2) {
+        thrown = (0..hand.len()).filter(|&position| count(&hand[position]) < 2).collect();
+    } else if let Some(highest) = (0..hand.len()).max_by_key(|&position| hand[position].rank()) {
+        thrown = (0..hand.len()).filter(|&position| position != highest).collect();
+    }
+    Discards::from_positions(&thrown)
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::card::parse_cards;
+    use crate::cards::deck::Deck;
+    use crate::game::config::GameConfig;
+    use crate::game::state::GameState;
+    use crate::test_util::{decide, holdem};
+
+    #[test]
+    fn test_preflop_chart() {
+        // Three handed with the button in seat 0 first to act: aces raise, seven deuce folds
+        let mut player = AIPlayer::new().with_seed(1);
+        let aces = holdem(3, "2c 3c As 7d 8d Ah 2s 7h 9c Jh 3d");
+        assert_eq!(decide(&mut player, &aces), Action::Raise(9));
+        let trash = holdem(3, "As Qd 7c Ks Qh 2d 2s 7h 9c Jh 3d");
+        assert_eq!(decide(&mut player, &trash), Action::Fold);
+        // In the small blind ace king suited calls a raise when playing tight and passive, and reraises when tight and aggressive
+        let raised = trash.apply(Action::Raise(8)).unwrap();
+        let mut passive = AIPlayer::new().with_style(Style { tightness: 1.0, aggression: 0.0 }).with_seed(1);
+        assert_eq!(decide(&mut passive, &raised), Action::Call);
+        let mut aggressive = AIPlayer::new().with_style(Style { tightness: 1.0, aggression: 1.0 }).with_seed(1);
+        assert_eq!(decide(&mut aggressive, &raised), Action::Raise(24));
     }
-    fn raise(&mut self, _current_bet: u32) -> Result<Action, PlayerError> {
-        todo!()
+    #[test]
+    fn test_preflop_tiers() {
+        let tier = |cards: &str| {
+            let cards = parse_cards(cards).unwrap();
+            preflop_tier(cards[0], cards[1])
+        };
+        assert_eq!(tier("As Ah"), 1);
+        assert_eq!(tier("Ks As"), 1);
+        assert_eq!(tier("Ah Kd"), 2);
+        assert_eq!(tier("Th Jh"), 3);
+        assert_eq!(tier("Jh Td"), 5);
+        assert_eq!(tier("2c 2d"), 7);
+        assert_eq!(tier("5s As"), 5);
+        assert_eq!(tier("5d Ac"), 9);
+        assert_eq!(tier("7c 2d"), 9);
     }
-    fn allin(&mut self) -> Action {
-        todo!()
+    #[test]
+    fn test_stud_third_street_goes_by_equity() {
+        // Heads up stud, seat 1 shows the deuce and brings it in with seven three, seat 0 completes with rolled up aces
+        let config = GameConfig::stud(PokerVariant::SevenCardStud, 1, 2, 10, 20);
+        let state = GameState::new(config, vec![100, 100], 1, Deck::stacked("Ah 7d As 3c Ac 2h")).unwrap();
+        let mut player = AIPlayer::new().with_samples(200).with_seed(5);
+        assert_eq!(decide(&mut player, &state), Action::Bet(2));
+        let state = state.apply(Action::Bet(2)).unwrap();
+        assert_eq!(decide(&mut player, &state), Action::Raise(10));
     }
-    fn win(&mut self, _amount: u32) {
-        todo!()
+    #[test]
+    fn test_postflop_equity_and_pot_odds() {
+        // Heads up, seat 1 flops top set and bets, seat 0 has nothing
+        let state = holdem(2, "Ks 2d Kh 7c Kd 9s 4h Jc 3c");
+        let state = state.apply(Action::Call).unwrap().apply(Action::Check).unwrap();
+        let mut player = AIPlayer::new().with_samples(200).with_seed(3);
+        assert!(matches!(decide(&mut player, &state), Action::Bet(_) | Action::AllIn(_)));
+        let state = state.apply(Action::Bet(4)).unwrap();
+        assert_eq!(decide(&mut player, &state), Action::Fold);
     }
-    fn get_chips(&self) -> u32 {
-        todo!()
+    #[test]
+    fn test_discards() {
+        let hand = parse_cards("Ah Kd Kc 7s 2h").unwrap();
+        assert_eq!(discards(PokerVariant::FiveCard, &hand).positions(), vec![0, 3, 4]);
+        let flush = parse_cards("Ah Kh 3h 7s 2h").unwrap();
+        assert_eq!(discards(PokerVariant::FiveCard, &flush).positions(), vec![3]);
+        let nothing = parse_cards("9h Kd 3c 7s 2h").unwrap();
+        assert_eq!(discards(PokerVariant::FiveCard, &nothing).positions(), vec![0, 2, 3, 4]);
+        let low = parse_cards("2h 7d 7c As 3h").unwrap();
+        assert_eq!(discards(PokerVariant::TwoSevenTripleDraw, &low).positions(), vec![2, 3]);
     }
 }
@@ -1,53 +1,46 @@
 use crate::players::action::Action;
-use crate::cards::card::Card;
-use thiserror::Error;
-
-pub struct PlayerAction<'a>(&'a dyn PlayerFunctions, Action); // Box is a smart pointer(allocates on heap) while dyn is a trait object
+use crate::game::config::GameConfig;
+use crate::game::legal::LegalActions;
+use crate::game::observation::Observation;
+
+// What an agent decided to do with its turn
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Decision {
+    Act(Action),  // Take the action, the table asks again if it breaks the rules
+    CheckOrFold,  // Give the decision up, the same as running out of time
+}
 
-impl<'a> PlayerAction<'a> {
-    
-    pub fn new(player: &'a dyn PlayerFunctions, action: Action) -> Self {
-        PlayerAction(player, action)
-    }
-    pub fn action(&self) -> Action {
-        self.1
-    }
-    pub fn player(&self) -> &'a dyn PlayerFunctions {
-        self.0
+impl Decision {
+    // The action the table plays for this decision
+    pub fn action(self, legal: &LegalActions) -> Action {
+        match self {
+            Decision::Act(action) => action,
+            Decision::CheckOrFold => legal.default_action(),
+        }
     }
 }
 
-pub trait PlayerFunctions {
-
-    fn receive_card(&mut self, card: Card)-> Result<(), PlayerError>;
-
-    fn get_action(&mut self, current_bet: u32) -> PlayerAction;
-
-    fn call(&mut self, current_bet: u32) -> Action;
-
-    fn raise(&mut self, current_bet: u32)-> Result<Action, PlayerError> ;
-
-    fn allin(&mut self) -> Action;
-
-    fn win(&mut self, amount: u32);
-
-    fn get_chips(&self) -> u32;
-
+impl From<Action> for Decision {
+    fn from(action: Action) -> Self {
+        Decision::Act(action)
+    }
 }
 
-pub struct InstanceInfo {
-    cards: Vec<Card>,
-    current_bet: u32,
-}
+// Anything that can sit at a table and play: people at the keyboard, bots, solvers
+// Agents only decide what to do, the stacks and pots are kept and moved by the game state so no agent has to know the rules
+// for chips, and an agent only ever sees the hand through its observation, so it can't look at anyone else's cards
+pub trait Agent: std::fmt::Debug {
+    fn name(&self) -> &str;
 
-#[derive(Debug, Error)]
-pub enum PlayerError {
-    PlayerError,
-    HumanPlayerError(String),
-}
+    fn decide(&mut self, observation: &Observation, legal: &LegalActions) -> Decision;
+
+    // Asked when everyone is all in whether to run the board times times, agents who don't say want it run once
+    fn agree_to_runouts(&mut self, _times: usize) -> bool {
+        false
+    }
 
-impl std::fmt::Display for PlayerError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}", self)
+    // Asked for the index of the next game when it is the agent's turn to pick in dealer's choice, the first game by default
+    fn choose_game(&mut self, _games: &[GameConfig]) -> usize {
+        0
     }
 }
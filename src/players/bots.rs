@@ -0,0 +1,309 @@
+use std::collections::VecDeque;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use crate::game::equity::{chen_score, estimate_equity};
+use crate::game::legal::LegalActions;
+use crate::game::observation::Observation;
+use crate::game::variant::PokerVariant;
+use crate::players::action::Action;
+use crate::players::ai_player::discards;
+use crate::players::base::{Agent, Decision};
+
+// Fixed opponents with known ways to beat them, for measuring other agents and for tests
+// None of them look at the rules beyond the legal actions, so they sit in any game
+
+// Never folds: checks when it can, otherwise calls, and brings it in or stands pat when it has to
+// Beaten by value betting and never bluffing
+#[derive(Debug)]
+pub struct CallStation {
+    name: String,
+}
+
+impl CallStation {
+    pub fn new() -> Self {
+        CallStation { name: String::from("Call Station") }
+    }
+
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = name.to_string();
+        self
+    }
+}
+
+impl Default for CallStation {
+    fn default() -> Self {
+        CallStation::new()
+    }
+}
+
+impl Agent for CallStation {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn decide(&mut self, _observation: &Observation, legal: &LegalActions) -> Decision {
+        if legal.call.is_some() {
+            Decision::Act(Action::Call)
+        } else {
+            Decision::CheckOrFold
+        }
+    }
+}
+
+// Always puts more chips in: bets or raises the smallest legal amount, goes all in when that is all it can raise,
+// only calls when the betting is capped and stands pat in a draw
+// Beaten by calling it down with good hands and letting it bet into them
+#[derive(Debug)]
+pub struct Maniac {
+    name: String,
+}
+
+impl Maniac {
+    pub fn new() -> Self {
+        Maniac { name: String::from("Maniac") }
+    }
+
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = name.to_string();
+        self
+    }
+}
+
+impl Default for Maniac {
+    fn default() -> Self {
+        Maniac::new()
+    }
+}
+
+impl Agent for Maniac {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn decide(&mut self, _observation: &Observation, legal: &LegalActions) -> Decision {
+        let action = if let Some(range) = legal.bet {
+            Action::Bet(range.min)
+        } else if let Some(range) = legal.raise {
+            Action::Raise(range.min)
+        } else if let Some(total) = legal.all_in {
+            Action::AllIn(total)
+        } else if legal.call.is_some() {
+            Action::Call
+        } else {
+            return Decision::CheckOrFold;
+        };
+        Decision::Act(action)
+    }
+}
+
+// Picks one of the legal actions at random, each as likely as the others, bets and raises of any legal size
+// and every way of drawing, the baseline every agent should beat
+#[derive(Debug)]
+pub struct RandomPlayer {
+    name: String,
+    rng: StdRng,
+}
+
+impl RandomPlayer {
+    pub fn new() -> Self {
+        RandomPlayer { name: String::from("Random Player"), rng: StdRng::from_entropy() }
+    }
+
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = name.to_string();
+        self
+    }
+
+    // Same player making its choices from a seed, so a session can be played again
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+}
+
+impl Default for RandomPlayer {
+    fn default() -> Self {
+        RandomPlayer::new()
+    }
+}
+
+impl Agent for RandomPlayer {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn decide(&mut self, _observation: &Observation, legal: &LegalActions) -> Decision {
+        // The listed actions have the smallest and largest sizes, the size is picked again from the whole range
+        let Some(&action) = legal.actions().choose(&mut self.rng) else {
+            return Decision::CheckOrFold;
+        };
+        let action = match (action, legal.bet, legal.raise) {
+            (Action::Bet(amount), Some(range), _) if Some(amount) != legal.bring_in => Action::Bet(self.rng.gen_range(range.min..=range.max)),
+            (Action::Raise(_), _, Some(range)) => Action::Raise(self.rng.gen_range(range.min..=range.max)),
+            _ => action,
+        };
+        Decision::Act(action)
+    }
+}
+
+// Tight and passive: only plays premium hands and never raises with them, just checks and calls
+// In hold'em before the flop the premiums are hands with 10 Chen points or more (tens or better, ace king, and ace queen, ace jack and king queen suited),
+// everywhere else hands with half again their fair share of the pot against random hands
+// Beaten by stealing the pots it won't play for and folding when it calls
+#[derive(Debug)]
+pub struct Rock {
+    name: String,
+    samples: usize, // Deals to estimate the equity from
+    rng: StdRng,
+}
+
+impl Rock {
+    pub fn new() -> Self {
+        Rock { name: String::from("Rock"), samples: 200, rng: StdRng::from_entropy() }
+    }
+
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = name.to_string();
+        self
+    }
+
+    // Same player with its equity estimates made from a seed
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+
+    fn is_premium(&mut self, observation: &Observation) -> bool {
+        if let (PokerVariant::TexasHoldem, 0, [first, second]) = (observation.config.variant, observation.street, &observation.hole_cards[..]) {
+            return chen_score(*first, *second) >= 10.0;
+        }
+        let players = observation.in_hand().len() as f64;
+        estimate_equity(observation, &[], self.samples, &mut self.rng) >= (1.5 / players).min(0.75)
+    }
+}
+
+impl Default for Rock {
+    fn default() -> Self {
+        Rock::new()
+    }
+}
+
+impl Agent for Rock {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn decide(&mut self, observation: &Observation, legal: &LegalActions) -> Decision {
+        if legal.draw.is_some() {
+            return Decision::Act(Action::Draw(discards(observation.config.variant, &observation.hole_cards)));
+        }
+        if legal.call.is_some() && legal.bring_in.is_none() && self.is_premium(observation) {
+            return Decision::Act(Action::Call);
+        }
+        Decision::CheckOrFold
+    }
+}
+
+// Replays a list of actions in order, one per decision, e.g. to put a hand history back on the table or set up a spot in a test
+// Once the list runs out, or when the next action isn't legal, it checks or folds (and the action is used up)
+#[derive(Debug)]
+pub struct ScriptedPlayer {
+    name: String,
+    script: VecDeque<Action>,
+}
+
+impl ScriptedPlayer {
+    pub fn new(script: Vec<Action>) -> Self {
+        ScriptedPlayer { name: String::from("Scripted Player"), script: script.into() }
+    }
+
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = name.to_string();
+        self
+    }
+
+    // Actions still to be played
+    pub fn remaining(&self) -> usize {
+        self.script.len()
+    }
+}
+
+impl Agent for ScriptedPlayer {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn decide(&mut self, _observation: &Observation, legal: &LegalActions) -> Decision {
+        match self.script.pop_front() {
+            Some(action) if legal.contains(&action) => Decision::Act(action),
+            _ => Decision::CheckOrFold,
+        }
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{decide, holdem};
+
+    #[test]
+    fn test_call_station_and_maniac() {
+        let state = holdem(2, "7c 2d 8s 3h 9c Jh 4s Kd 5c");
+        assert_eq!(decide(&mut CallStation::new(), &state), Action::Call);
+        assert_eq!(decide(&mut Maniac::new(), &state), Action::Raise(4));
+        let state = state.apply(Action::Call).unwrap();
+        assert_eq!(decide(&mut CallStation::new(), &state), Action::Check);
+        assert_eq!(decide(&mut Maniac::new(), &state), Action::Raise(4));
+        // Facing a raise of everything the maniac can only go all in
+        let state = state.apply(Action::Raise(100)).unwrap();
+        assert_eq!(decide(&mut Maniac::new(), &state), Action::AllIn(100));
+    }
+    #[test]
+    fn test_random_player_only_plays_legal_actions() {
+        let mut player = RandomPlayer::new().with_seed(5);
+        let mut state = holdem(2, "7c 2d 8s 3h 9c Jh 4s Kd 5c");
+        let mut raised = false;
+        for _ in 0..200 {
+            let legal = state.legal_actions().unwrap();
+            let action = decide(&mut player, &state);
+            assert!(legal.contains(&action), "{:?}", action);
+            raised |= matches!(action, Action::Raise(amount) if amount != 4 && amount != 100);
+            if action == Action::Fold {
+                state = holdem(2, "7c 2d 8s 3h 9c Jh 4s Kd 5c");
+            }
+        }
+        assert!(raised);
+    }
+    #[test]
+    fn test_rock_plays_premiums_passively() {
+        let mut rock = Rock::new().with_seed(2);
+        // Seven deuce folds to the big blind, kings just call it
+        assert_eq!(decide(&mut rock, &holdem(2, "Kc 7d 8s 2h 9c Jh 4s Kd 5c")), Action::Fold);
+        let kings = holdem(2, "2c Kd 8s Kh 9c Jh 4s 3d 5c");
+        assert_eq!(decide(&mut rock, &kings), Action::Call);
+        // King queen suited is as good as ten points, nines are one short
+        assert_eq!(decide(&mut rock, &holdem(2, "2c Kd 8s Qd 9c Jh 4s 3d 5c")), Action::Call);
+        assert_eq!(decide(&mut rock, &holdem(2, "2c 9d 8s 9h Tc Jh 4s 3d 5c")), Action::Fold);
+        // On the flop with an overpair it calls, with nothing it folds to a bet
+        let flop = kings.apply(Action::Call).unwrap().apply(Action::Check).unwrap();
+        let flop = flop.apply(Action::Bet(2)).unwrap();
+        assert_eq!(decide(&mut rock, &flop), Action::Call);
+        let missed = holdem(2, "Kc 7d 8s 2h 9c Jh 4s Qd 5c").apply(Action::Call).unwrap().apply(Action::Check).unwrap();
+        let missed = missed.apply(Action::Bet(2)).unwrap();
+        assert_eq!(decide(&mut rock, &missed), Action::Fold);
+    }
+    #[test]
+    fn test_scripted_player_replays_its_actions() {
+        let script: Vec<Action> = ["call", "bet 6", "raise 3", "draw 0 4", "pat", "allin 100"].iter().map(|action| action.parse().unwrap()).collect();
+        let mut player = ScriptedPlayer::new(script);
+        let state = holdem(2, "7c 2d 8s 3h 9c Jh 4s Kd 5c");
+        assert_eq!(decide(&mut player, &state), Action::Call);
+        let state = state.apply(Action::Call).unwrap();
+        // The big blind can check or raise after the call, but not bet as its blind already opened the betting,
+        // so the bet is used up and it checks instead
+        assert_eq!(decide(&mut player, &state), Action::Check);
+        assert_eq!(player.remaining(), 4);
+    }
+}
@@ -1,241 +1,370 @@
-use std::io; // Used for input
+use std::io::{self, BufRead, BufReader}; // Used for input
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{Mutex, OnceLock};
+use std::thread;
+use std::time::Instant;
 use thiserror::Error; // Used for custom errors
 use crate::cards::card::Card;
-use crate::players::action::Action;
-use crate::players::base::{PlayerFunctions, PlayerAction, PlayerError};
+use crate::game::config::GameConfig;
+use crate::game::legal::LegalActions;
+use crate::game::observation::Observation;
+use crate::players::action::{Action, ActionError, Discards};
+use crate::players::base::{Agent, Decision};
 
-#[derive(Debug, Error)]
+#[derive(Debug, Error, PartialEq, Eq)]
 pub enum HumanError {
-    #[error("Raise must be at least {0}")]
-    RaiseMustBeAtLeast(u32),
-    
-}
-
-impl From<HumanError> for PlayerError {
-    fn from(value: HumanError) -> Self {
-        PlayerError::HumanPlayerError(value.to_string())
-    }
+    #[error(transparent)]
+    InvalidAction(#[from] ActionError),
+    #[error("Invalid cards to discard: {0}")]
+    InvalidDiscards(String),
 }
 
 
 #[derive(Debug)]
 pub struct HumanPlayer {
     name: String,
-    hand: Vec<Card>,
-    chips: u32,
-    bet_in_round: u32,
+    keyboard: &'static Keyboard, // Where the player's answers are typed
 }
 
-impl PlayerFunctions for HumanPlayer {
+impl Agent for HumanPlayer {
 
-    fn receive_card(&mut self, card: Card)-> Result<(), PlayerError>{
-        self.hand.push(card);
-        Ok(())
-    }
-
-    fn get_action(&mut self, current_bet: u32) -> PlayerAction {
-        self.get_action_with_input(current_bet, None)
+    fn name(&self) -> &str {
+        &self.name
     }
 
-    fn call(&mut self, current_bet: u32) -> Action {
-        if current_bet > self.chips {
-            return self.allin();
+    // Asks until the input is an action, whether it is legal is up to the game state
+    // Checks or folds if there is no more input or the player runs out of time
+    fn decide(&mut self, observation: &Observation, legal: &LegalActions) -> Decision {
+        let deadline = observation.time_left.map(|left| Instant::now() + left);
+        if legal.draw.is_some() {
+            let positions: Vec<String> = observation.hole_cards.iter().enumerate().map(|(i, card)| format!("{}:{}", i + 1, card)).collect();
+            println!("{} holds {}", self.get_name(), positions.join(" "));
+            println!("Enter the positions of the cards to discard, or nothing to stand pat: ");
+        } else {
+            let hand: Vec<String> = observation.cards().iter().map(|card| card.to_string()).collect();
+            println!("{} holds {}, {} in the pot", self.get_name(), hand.join(" "), observation.pot);
+            println!("Enter your action {} ({}): ", self.get_name(), Self::describe(legal)); // Ask for input
+        }
+        loop {
+            let Some(input) = self.keyboard.read_line(deadline) else {
+                if let Some(left) = observation.time_left {
+                    println!("{} ran out of their {} seconds", self.get_name(), left.as_secs());
+                }
+                return Decision::CheckOrFold;
+            };
+            match Self::parse_action(observation, legal, &input) {
+                Ok(action) => return Decision::Act(action),
+                Err(e) => println!("{}, please try again!", e),
+            }
         }
-        self.chips -= current_bet; // Remove chips from player
-        self.bet_in_round += current_bet; // Tracks chips in pot
-        return Action::Call;
     }
 
-    fn raise(&mut self, current_bet: u32) -> Result<Action, PlayerError> {
-        self.get_raise_with_input(current_bet, None)
+    fn agree_to_runouts(&mut self, times: usize) -> bool {
+        println!("{}, run the board {} times? (y/n): ", self.get_name(), times);
+        Self::parse_agreement(&self.keyboard.read_line(None).unwrap_or_default())
     }
 
-    fn allin(&mut self) -> Action {
-        self.bet_in_round += self.chips; // Add all chips to the pot
-        self.chips = 0; // Remove all chips from player
-        return Action::AllIn(self.bet_in_round);
-    }
-    fn win(&mut self, pot: u32) {
-        self.chips += pot;
-    }
-    
-    fn get_chips(&self) -> u32 {
-        self.chips
+    fn choose_game(&mut self, games: &[GameConfig]) -> usize {
+        let names: Vec<String> = games.iter().enumerate().map(|(i, game)| format!("{}:{}", i + 1, game.variant)).collect();
+        println!("{}, it's your choice: {}", self.get_name(), names.join(", "));
+        Self::parse_choice(games, &self.keyboard.read_line(None).unwrap_or_default())
     }
 
 }
 
 impl HumanPlayer {
     // Allocates the structure to the heap
-    pub fn new(name: String, chips: u32) -> HumanPlayer {
-        HumanPlayer { name, hand: Vec::new(), chips, bet_in_round: 0 }
+    pub fn new(name: String) -> HumanPlayer {
+        HumanPlayer { name, keyboard: Keyboard::stdin() }
     }
-    // Overloaded get_action to take an input
-    fn get_action_with_input(&mut self, current_bet: u32, input: Option<&str>) -> PlayerAction {
-        loop {
-            println!("Enter your action {} (Check, Fold, Call, Raise): ", self.get_name()); // Ask for input
-            
-            let input = if let Some(input) = input{ // If input is provided, convert to string
-                input.to_string()
-            } else { // If no input is provided, read from stdin
-                let mut input = String::new();
-                if io::stdin().read_line(&mut input).is_err() {
-                    println!("Error reading input!");
-                    continue;
-                }
-                input.trim().to_lowercase()
-            };
 
-            match input.to_lowercase().trim() { // Remove whitespace from input and converts to str slice
-                "check" => {
-                    // If the current bet is greater than 0, the player must call
-                    if current_bet > 0 || self.bet_in_round != current_bet {
-                        println!("Active bet is {}, you must call or fold!", current_bet);
-                        continue;
-                    }
-                    return PlayerAction::new(self, Action::Check);
-                },
-                "fold" => {
-                    return PlayerAction::new(self, Action::Fold);
-                },
-                "call" => {
-                    let action = self.call(current_bet);
-                    return PlayerAction::new(self, action);
-                },
-                "raise" => {
-                    match self.raise(current_bet) {
-                        Ok(action) => {
-                            return PlayerAction::new(self, action);
-                        },
-                        Err(e) => {
-                            println!("{}", e);
-                            continue;
-                        },
-                    }
-                },
-                _ => { // Any other input
-                    println!("Invalid action, please try again!");
-                    continue;
-                },
-            }
-        }
+    // Same player answering from another keyboard
+    pub fn with_keyboard(mut self, keyboard: &'static Keyboard) -> Self {
+        self.keyboard = keyboard;
+        self
     }
 
-    fn get_raise_with_input(&mut self, current_bet: u32, input: Option<&str>) -> Result<Action, PlayerError> {
-        loop {
+    // Turns a line of input into an action, whether it is legal is up to the game state
+    // Actions are written as in Action::from_str, e.g. "call" or "raise 30", and "all in" goes all in for whatever the player has
+    // A bet or raise is whichever the player can make, so "bet 30" facing a bet raises to 30, and a number on its own is the amount
+    // In a draw the cards to discard are entered by their position in the hand instead, see parse_discards
+    fn parse_action(observation: &Observation, legal: &LegalActions, input: &str) -> Result<Action, HumanError> {
+        if legal.draw.is_some() {
+            return Self::parse_discards(&observation.hole_cards, input);
+        }
+        let input = input.trim().to_lowercase();
+        if input.replace(' ', "") == "allin" {
+            return Ok(Action::AllIn(legal.all_in.unwrap_or_default()));
+        }
+        let action = match input.parse::<u32>() {
+            Ok(amount) => Action::Bet(amount),
+            Err(_) => input.parse::<Action>()?,
+        };
+        Ok(match action {
+            Action::Bet(amount) | Action::Raise(amount) if legal.bring_in == Some(amount) => Action::Bet(amount),
+            Action::Bet(amount) | Action::Raise(amount) if legal.bet.is_none() && legal.raise.is_some() => Action::Raise(amount),
+            Action::Raise(amount) if legal.bet.is_some() => Action::Bet(amount),
+            action => action,
+        })
+    }
 
-            let trimmed_input = if let Some(input) = input {
-                input.to_string() // Use provided input
+    // Cards to discard are entered by their position in the hand, 1 being the first card, e.g. "1 4 5"
+    // Nothing (or "pat") keeps every card
+    fn parse_discards(hand: &[Card], input: &str) -> Result<Action, HumanError> {
+        let input = input.trim().to_lowercase();
+        if input.is_empty() || input == "pat" {
+            return Ok(Action::Draw(Discards::none()));
+        }
+        let positions: Result<Vec<usize>, _> = input
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|token| !token.is_empty())
+            .map(|token| token.parse::<usize>())
+            .collect();
+        match positions {
+            Ok(positions) if positions.iter().all(|&position| (1..=hand.len()).contains(&position)) => {
+                let positions: Vec<usize> = positions.iter().map(|position| position - 1).collect();
+                Ok(Action::Draw(Discards::from_positions(&positions)))
             }
-            else{
-                println!("Enter the amount you want to Raise or go All In: ");
-                let mut input = String::new(); // Reset the input each loop
-                if io::stdin().read_line(&mut input).is_err() {
-                    println!("Error reading input!");
-                    continue;
-                }
-                input.replace(" ", "").to_lowercase().trim().to_string()
-            };
+            _ => Err(HumanError::InvalidDiscards(input)),
+        }
+    }
 
-            // Check for all in
-            if trimmed_input == "allin" {
-                return Ok(self.allin());
-            }
+    // Anything but yes runs the board once
+    fn parse_agreement(input: &str) -> bool {
+        matches!(input.trim().to_lowercase().as_str(), "y" | "yes")
+    }
 
-            // Check for a raise
-            if let Ok(amount) = trimmed_input.parse::<u32>() { // Parses the trimmed input to a u32
-                // If amount typed is greater than the player's chips, go all in
-                if amount > self.chips {
-                    return Ok(self.allin());
-                }
-                // Raise must be at least double including the current bet
-                if amount < 2 * current_bet {
-                    return Err(HumanError::RaiseMustBeAtLeast(current_bet).into());
-                }
-                self.chips -= amount;
-                self.bet_in_round += amount;
-                    return Ok(Action::Raise(amount));
-                }
+    // Games are picked by their number in the list, 1 being the first, anything else picks the first game
+    fn parse_choice(games: &[GameConfig], input: &str) -> usize {
+        match input.trim().parse::<usize>() {
+            Ok(choice) if (1..=games.len()).contains(&choice) => choice - 1,
+            _ => 0,
+        }
+    }
 
-            println!("Invalid amount, please try again!");
+    // Short description of the legal actions for the prompt
+    fn describe(legal: &LegalActions) -> String {
+        let mut options = Vec::new();
+        if legal.can_fold() {
+            options.push(String::from("Fold"));
+        }
+        if let Some(amount) = legal.bring_in {
+            options.push(format!("Bet {} to bring it in", amount));
+        }
+        if legal.can_check {
+            options.push(String::from("Check"));
+        }
+        if let Some(call) = legal.call {
+            options.push(format!("Call {}", call));
+        }
+        if let Some(range) = legal.bet {
+            options.push(format!("Bet {}-{}", range.min, range.max));
+        }
+        if let Some(range) = legal.raise {
+            options.push(format!("Raise {}-{}", range.min, range.max));
         }
+        if let Some(total) = legal.all_in {
+            options.push(format!("All In {}", total));
+        }
+        options.join(", ")
     }
+
     pub fn get_name(&self) -> &str {
         &self.name
     }
 }
 
+// Lines typed at the keyboard, read on a thread of their own so waiting for a player can stop when their time is up
+// Every question asked at the keyboard has to be read through the same Keyboard, the reader thread takes every line
+// so one read straight from the input would miss its answer
+#[derive(Debug)]
+pub struct Keyboard {
+    lines: Mutex<Lines>,
+}
+
+#[derive(Debug)]
+struct Lines {
+    receiver: Receiver<(Instant, String)>, // Every line with when it was typed
+    timed_out: bool,                       // The last question ran out of time, its answer may still come in
+}
+
+impl Keyboard {
+    // Reads the lines of any input, e.g. a script of answers
+    pub fn new<R: BufRead + Send + 'static>(mut input: R) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || loop {
+            let mut line = String::new();
+            // Once the input ends the sender is dropped and every read after it gets None
+            match input.read_line(&mut line) {
+                Ok(0) | Err(_) => break,
+                Ok(_) if sender.send((Instant::now(), line)).is_err() => break,
+                Ok(_) => {}
+            }
+        });
+        Keyboard { lines: Mutex::new(Lines { receiver, timed_out: false }) }
+    }
+
+    // The standard input, shared by the human players and every other question the program asks
+    pub fn stdin() -> &'static Keyboard {
+        static STDIN: OnceLock<Keyboard> = OnceLock::new();
+        STDIN.get_or_init(|| Keyboard::new(BufReader::new(io::stdin())))
+    }
+
+    // A line typed before the deadline, None once there is no more input or the time is up
+    // A line typed after a question ran out of time but before the next one was asked answers the one that ran out, so it is skipped
+    pub fn read_line(&self, deadline: Option<Instant>) -> Option<String> {
+        let asked = Instant::now();
+        let mut lines = self.lines.lock().ok()?;
+        loop {
+            let (typed, line) = match deadline {
+                Some(deadline) => match lines.receiver.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                    Ok(line) => line,
+                    Err(RecvTimeoutError::Timeout) => {
+                        lines.timed_out = true;
+                        return None;
+                    }
+                    Err(RecvTimeoutError::Disconnected) => return None,
+                },
+                None => lines.receiver.recv().ok()?,
+            };
+            if !std::mem::take(&mut lines.timed_out) || typed >= asked {
+                return Some(line);
+            }
+        }
+    }
+
+    // Asks a question and waits for the answer, trimmed, empty once there is no more input
+    pub fn prompt(&self, question: &str) -> String {
+        println!("{}", question);
+        self.read_line(None).map(|line| line.trim().to_string()).unwrap_or_default()
+    }
+}
+
+// Asks a question at the standard input, see Keyboard::prompt
+pub fn prompt(question: &str) -> String {
+    Keyboard::stdin().prompt(question)
+}
+
 // Unit tests
 #[cfg(test)] // Trait to only compile cargo test is ran
 mod tests {
     use super::*; // Import all code from parent module
+    use crate::cards::card::parse_cards;
+    use crate::game::legal::BetRange;
+    use crate::game::round::BettingRound;
+    use crate::game::variant::PokerVariant;
+
+    // Heads up hand seen from seat 0 holding the given cards
+    fn holding(cards: &str) -> Observation {
+        Observation {
+            seat: 0,
+            button: 0,
+            street: 0,
+            hole_cards: parse_cards(cards).unwrap(),
+            up_cards: vec![Vec::new(); 2],
+            board: Vec::new(),
+            boards: vec![Vec::new()],
+            stacks: vec![100, 100],
+            committed: vec![0, 0],
+            bets: vec![0, 0],
+            pot: 0,
+            folded: vec![false, false],
+            history: Vec::new(),
+            config: GameConfig::new(PokerVariant::TexasHoldem, 1, 2),
+            round: BettingRound::new(2, 2),
+            time_left: None,
+        }
+    }
+
+    // Legal actions facing a bet of current_bet with the given stack
+    fn facing(current_bet: u32, min_raise: u32, stack: u32) -> LegalActions {
+        LegalActions {
+            seat: 0,
+            can_check: current_bet == 0,
+            call: if current_bet > 0 { Some(current_bet.min(stack)) } else { None },
+            bet: if current_bet == 0 { Some(BetRange { min: min_raise, max: stack }) } else { None },
+            raise: if current_bet > 0 { Some(BetRange { min: min_raise, max: stack }) } else { None },
+            all_in: Some(stack),
+            bring_in: None,
+            draw: None,
+        }
+    }
+
     #[test]
     fn test_human_player_new() { // Test if we can create a new human player
-        let player1 = HumanPlayer::new(String::from("Player 1"), 100);
+        let player1 = HumanPlayer::new(String::from("Player 1"));
         assert_eq!(player1.name, "Player 1");
-        assert_eq!(player1.chips, 100);
     }
     #[test]
     fn test_human_check() { // Test if we can get an action from a human player with an input
-        let mut player1 = HumanPlayer::new(String::from("Player 1"), 100);
-        let action = player1.get_action_with_input(0, Some("check"));
-        assert_eq!(action.action(), Action::Check);
-        assert_eq!(player1.chips, 100);
-        assert_eq!(player1.bet_in_round, 0);
+        let action = HumanPlayer::parse_action(&holding("As Kd"), &facing(0, 2, 100), "check");
+        assert_eq!(action, Ok(Action::Check));
     }
     #[test]
     fn test_human_fold() {
-        let mut player1 = HumanPlayer::new(String::from("Player 1"), 100);
-        let action = player1.get_action_with_input(10, Some("fold"));
-        assert_eq!(action.action(), Action::Fold);
+        let action = HumanPlayer::parse_action(&holding("As Kd"), &facing(10, 20, 100), "Fold");
+        assert_eq!(action, Ok(Action::Fold));
     }
     #[test]
     fn test_human_call() {
-        let mut player1 = HumanPlayer::new(String::from("Player 1"), 100);
-        let action = player1.get_action_with_input(10, Some("call"));
-        assert_eq!(action.action(), Action::Call);
-        assert_eq!(player1.chips, 90);
-        assert_eq!(player1.bet_in_round, 10);
+        let action = HumanPlayer::parse_action(&holding("As Kd"), &facing(10, 20, 100), " call ");
+        assert_eq!(action, Ok(Action::Call));
     }
     #[test]
     fn test_human_raise() {
-        let mut player1 = HumanPlayer::new(String::from("Player 1"), 100);
-        // Raise 30
-        let action = player1.get_raise_with_input(10, Some("30"));
-        assert_eq!(action.unwrap(), Action::Raise(30));
-        assert_eq!(player1.chips, 70);
-        assert_eq!(player1.bet_in_round, 30);
-        // Raise 60
-        let action2 = player1.get_raise_with_input(30, Some("60"));
-        assert_eq!(action2.unwrap(), Action::Raise(60));
-        assert_eq!(player1.chips, 10);
-        assert_eq!(player1.bet_in_round, 90);
-        // All in
-        let action3 = player1.get_raise_with_input(60, Some("20"));
-        assert_eq!(action3.unwrap(), Action::AllIn(100));
-        assert_eq!(player1.chips, 0);
-        assert_eq!(player1.bet_in_round, 100);
+        // Raise to 30 over a bet of 10, written either way or as the amount
+        assert_eq!(HumanPlayer::parse_action(&holding("As Kd"), &facing(10, 20, 100), "raise 30"), Ok(Action::Raise(30)));
+        assert_eq!(HumanPlayer::parse_action(&holding("As Kd"), &facing(10, 20, 100), "bet 30"), Ok(Action::Raise(30)));
+        assert_eq!(HumanPlayer::parse_action(&holding("As Kd"), &facing(10, 20, 100), "30"), Ok(Action::Raise(30)));
+        // Nobody has bet yet so it is a bet
+        assert_eq!(HumanPlayer::parse_action(&holding("As Kd"), &facing(0, 2, 100), "raise 30"), Ok(Action::Bet(30)));
     }
     #[test]
     fn test_human_allin() {
-        let mut player1 = HumanPlayer::new(String::from("Player 1"), 100);
-        let action1 = player1.get_raise_with_input(0, Some("20"));
-        assert_eq!(action1.unwrap(), Action::Raise(20));
-        assert_eq!(player1.chips, 80);
-        assert_eq!(player1.bet_in_round, 20);
-
-        let action = player1.get_raise_with_input(10, Some("allin"));
-        assert_eq!(action.unwrap(), Action::AllIn(100));
-        assert_eq!(player1.chips, 0);
-        assert_eq!(player1.bet_in_round, 100);
+        assert_eq!(HumanPlayer::parse_action(&holding("As Kd"), &facing(10, 20, 100), "allin"), Ok(Action::AllIn(100)));
+        assert_eq!(HumanPlayer::parse_action(&holding("As Kd"), &facing(10, 20, 100), "all in"), Ok(Action::AllIn(100)));
     }
     #[test]
-    fn test_human_raise_must_be_at_least() {
-        let mut player1 = HumanPlayer::new(String::from("Player 1"), 100);
-        let action = player1.get_raise_with_input(15, Some("20"));
-        assert_eq!(action.is_err(), true);
-        assert_eq!(player1.chips, 100);
-        assert_eq!(player1.bet_in_round, 0);
+    fn test_human_does_not_validate_raise() {
+        // A raise below the minimum is passed on, the game state is the one to reject it
+        assert_eq!(HumanPlayer::parse_action(&holding("As Kd"), &facing(15, 30, 100), "raise 20"), Ok(Action::Raise(20)));
+    }
+    #[test]
+    fn test_human_draw() {
+        let drawing = LegalActions { draw: Some(5), ..facing(0, 2, 100) };
+        let hand = holding("As Kd Qh Jc 2s");
+        assert_eq!(HumanPlayer::parse_action(&hand, &drawing, "1 4, 5"), Ok(Action::Draw(Discards::from_positions(&[0, 3, 4]))));
+        assert_eq!(HumanPlayer::parse_action(&hand, &drawing, "pat"), Ok(Action::Draw(Discards::none())));
+        assert_eq!(HumanPlayer::parse_action(&hand, &drawing, "6"), Err(HumanError::InvalidDiscards(String::from("6"))));
+    }
+    #[test]
+    fn test_human_runouts() {
+        assert!(HumanPlayer::parse_agreement("Y"));
+        assert!(!HumanPlayer::parse_agreement("no"));
+        assert!(!HumanPlayer::parse_agreement(""));
+    }
+    #[test]
+    fn test_human_choose_game() {
+        let games = [GameConfig::limit(PokerVariant::TexasHoldem, 2, 4), GameConfig::limit(PokerVariant::Razz, 2, 4)];
+        assert_eq!(HumanPlayer::parse_choice(&games, "2"), 1);
+        assert_eq!(HumanPlayer::parse_choice(&games, "3"), 0);
+    }
+    #[test]
+    fn test_human_invalid_input() {
+        // Input that isn't an action is an error rather than asked for again
+        let error = HumanPlayer::parse_action(&holding("As Kd"), &facing(15, 30, 100), "raise lots");
+        assert_eq!(error, Err(HumanError::InvalidAction(ActionError::Invalid(String::from("raise lots")))));
+        assert!(HumanPlayer::parse_action(&holding("As Kd"), &facing(15, 30, 100), "raise").is_err());
+    }
+    #[test]
+    fn test_decision_then_prompt_share_the_keyboard() {
+        // The player folds, then the question after the hand gets the next line, as main asks "Play another hand?"
+        let keyboard: &'static Keyboard = Box::leak(Box::new(Keyboard::new(io::Cursor::new("fold\ny\n"))));
+        let mut player = HumanPlayer::new(String::from("Alice")).with_keyboard(keyboard);
+        assert_eq!(player.decide(&holding("As Kd"), &facing(2, 4, 100)), Decision::Act(Action::Fold));
+        assert_eq!(keyboard.prompt("Play another hand? (y/n): "), "y");
+        // Once the input is over the player checks or folds and questions get nothing
+        assert_eq!(player.decide(&holding("As Kd"), &facing(2, 4, 100)), Decision::CheckOrFold);
+        assert_eq!(keyboard.prompt("Play another hand? (y/n): "), "");
     }
 }
-
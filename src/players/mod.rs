@@ -3,3 +3,6 @@ pub mod player;
 pub mod action;
 mod human_player;
 mod ai_player;
+mod bots;
+mod monte_carlo;
+pub mod registry;
@@ -0,0 +1,254 @@
+use std::time::{Duration, Instant};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use crate::cards::deck::Deck;
+use crate::game::equity::{ranges_from_betting, CardSampler};
+use crate::game::legal::LegalActions;
+use crate::game::observation::Observation;
+use crate::game::state::GameState;
+use crate::players::action::Action;
+use crate::players::base::{Agent, Decision};
+
+// Part of the table's clock it thinks for, the rest is kept back so a slow rollout or a busy machine doesn't time it out
+const CLOCK_SHARE: u32 = 2;
+
+// How everyone plays the rest of the hand in a rollout, once the action being tried has been taken
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Continuation {
+    CheckDown, // Everyone checks and calls to the showdown and stands pat, so an action is worth the share of the pot it gets to see
+    Random,    // Everyone takes one of the legal actions at random, as the random player does
+}
+
+// Plays the action with the most chips expected: at its turn it deals the cards it can't see many times,
+// tries every legal action on each deal (the smallest and largest bets and raises, half the pot and the pot)
+// and plays the hand out with the continuation, a stronger baseline than the rule based player that is still no solver
+#[derive(Debug)]
+pub struct MonteCarloPlayer {
+    name: String,
+    iterations: usize,            // Rollouts of every action
+    time_limit: Option<Duration>, // Stops rolling out once the time is up even if not every rollout is done
+    ranges: bool,                 // Deals the others hands from their ranges read from the betting rather than any hand
+    continuation: Continuation,
+    rng: StdRng,
+}
+
+impl MonteCarloPlayer {
+    pub fn new() -> Self {
+        MonteCarloPlayer {
+            name: String::from("Monte Carlo Player"),
+            iterations: 200,
+            time_limit: None,
+            ranges: true,
+            continuation: Continuation::CheckDown,
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = name.to_string();
+        self
+    }
+
+    // Same player rolling out every action the given number of times
+    pub fn with_iterations(mut self, iterations: usize) -> Self {
+        self.iterations = iterations.max(1);
+        self
+    }
+
+    // Same player deciding within the time limit, every action is still rolled out at least once
+    pub fn with_time_limit(mut self, time_limit: Duration) -> Self {
+        self.time_limit = Some(time_limit);
+        self
+    }
+
+    // Same player dealing the others hands from their ranges, or any hands
+    pub fn with_ranges(mut self, ranges: bool) -> Self {
+        self.ranges = ranges;
+        self
+    }
+
+    pub fn with_continuation(mut self, continuation: Continuation) -> Self {
+        self.continuation = continuation;
+        self
+    }
+
+    // Same player dealing its rollouts from a seed, so it always plays a spot the same way within the same number of rollouts
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+
+    // Chips each of the actions tried is expected to win or lose in the hand, counting what is already in the pot
+    // Every action is rolled out on the same deals so they are compared on the same cards
+    pub fn evaluate(&mut self, observation: &Observation, legal: &LegalActions) -> Vec<(Action, f64)> {
+        let actions = candidates(observation, legal);
+        let ranges = if self.ranges { ranges_from_betting(observation) } else { Vec::new() };
+        let Some(sampler) = CardSampler::new(observation, &ranges, &mut self.rng) else {
+            return actions.into_iter().map(|action| (action, 0.0)).collect();
+        };
+        let time_limit = self.thinking_time(observation);
+        let started = Instant::now();
+        let mut totals = vec![0.0; actions.len()];
+        let mut rollouts = 0;
+        while rollouts < self.iterations && (rollouts == 0 || time_limit.is_none_or(|limit| started.elapsed() < limit)) {
+            let (hands, pool) = sampler.sample(&mut self.rng);
+            let Ok(state) = GameState::from_observation(observation, hands, Deck::from_cards(pool)) else {
+                break;
+            };
+            for (total, &action) in totals.iter_mut().zip(&actions) {
+                *total += self.rollout(&state, action) as f64;
+            }
+            rollouts += 1;
+        }
+        actions.into_iter().zip(totals).map(|(action, total)| (action, total / rollouts.max(1) as f64)).collect()
+    }
+
+    // Longest it rolls out for, its own time limit or half the time the table's clock leaves it when that is shorter
+    pub fn thinking_time(&self, observation: &Observation) -> Option<Duration> {
+        self.time_limit.into_iter().chain(observation.time_left.map(|left| left / CLOCK_SHARE)).min()
+    }
+
+    // Chips the observer wins or loses once the action is taken and everyone plays on with the continuation
+    fn rollout(&mut self, state: &GameState, action: Action) -> i64 {
+        let seat = state.to_act().unwrap_or_default();
+        let Ok(mut state) = state.apply(action) else {
+            return 0;
+        };
+        while let Some(legal) = state.legal_actions() {
+            let next = match self.continuation {
+                Continuation::CheckDown if legal.call.is_some() => Action::Call,
+                Continuation::CheckDown => legal.default_action(),
+                Continuation::Random => legal.actions().choose(&mut self.rng).copied().unwrap_or_else(|| legal.default_action()),
+            };
+            match state.apply(next) {
+                Ok(next) => state = next,
+                Err(_) => break,
+            }
+        }
+        state.utility(seat).unwrap_or_default()
+    }
+}
+
+impl Default for MonteCarloPlayer {
+    fn default() -> Self {
+        MonteCarloPlayer::new()
+    }
+}
+
+impl Agent for MonteCarloPlayer {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn decide(&mut self, observation: &Observation, legal: &LegalActions) -> Decision {
+        // The first of the best actions, the cheapest
+        let mut best: Option<(Action, f64)> = None;
+        for (action, value) in self.evaluate(observation, legal) {
+            if best.is_none_or(|(_, most)| value > most) {
+                best = Some((action, value));
+            }
+        }
+        match best {
+            Some((action, _)) => Decision::Act(action),
+            None => Decision::CheckOrFold,
+        }
+    }
+
+    // Running it more than once only lowers the variance, so it always agrees
+    fn agree_to_runouts(&mut self, _times: usize) -> bool {
+        true
+    }
+}
+
+// Actions worth trying: the legal ones with bets and raises of half the pot and the pot added
+// Ties between actions go to the first, so the cheaper actions come first
+fn candidates(observation: &Observation, legal: &LegalActions) -> Vec<Action> {
+    let mut actions = legal.actions();
+    let current_bet = observation.round.current_bet();
+    let after_call = observation.pot + observation.to_call();
+    for size in [after_call / 2, after_call] {
+        let total = current_bet + size;
+        let action = match (legal.bet, legal.raise) {
+            (Some(range), _) if range.contains(total) => Action::Bet(total),
+            (_, Some(range)) if range.contains(total) => Action::Raise(total),
+            _ => continue,
+        };
+        if !actions.contains(&action) && legal.all_in != Some(total) {
+            actions.push(action);
+        }
+    }
+    actions.sort_by_key(|action| match action {
+        Action::Fold => 0,
+        Action::Check => 1,
+        Action::Call => 2,
+        _ => 3 + action.amount(),
+    });
+    actions
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::clock::ManualClock;
+    use crate::game::timer::{ActionTimer, TimeControl};
+    use crate::test_util::{decide, holdem};
+
+    #[test]
+    fn test_candidate_actions() {
+        let state = holdem(2, "7c 2d 8s 3h 9c Jh 4s Kd 5c");
+        let legal = state.legal_actions().unwrap();
+        // Facing the big blind with 3 in the pot: half the pot after calling is a raise to 4, the pot a raise to 6
+        let actions = candidates(&Observation::new(&state, 0), &legal);
+        assert_eq!(actions, vec![Action::Fold, Action::Call, Action::Raise(4), Action::Raise(6), Action::AllIn(100)]);
+    }
+    #[test]
+    fn test_rollouts_find_the_value_of_the_cards() {
+        // On the river with the nuts every bet wins more than checking, with nothing it folds to a bet
+        let state = holdem(2, "2d As 3c Ah Ad Ac 9c Jh 4c");
+        let river = [Action::Call, Action::Check, Action::Check, Action::Check, Action::Check, Action::Check, Action::Bet(2)];
+        let state = river.iter().fold(state, |state, &action| state.apply(action).unwrap());
+        let mut player = MonteCarloPlayer::new().with_iterations(50).with_seed(4);
+        assert!(matches!(decide(&mut player, &state), Action::Raise(_) | Action::AllIn(_)));
+        let state = holdem(2, "As 2d Ah 3c Ad 7s 9c Jh 4c");
+        let state = river.iter().fold(state, |state, &action| state.apply(action).unwrap());
+        assert_eq!(decide(&mut player, &state), Action::Fold);
+    }
+    #[test]
+    fn test_same_seed_same_evaluation() {
+        let state = holdem(2, "7c Qd 8s Qh 9c Jh 4s Kd 5c");
+        let legal = state.legal_actions().unwrap();
+        let observation = Observation::new(&state, 0);
+        let mut first = MonteCarloPlayer::new().with_iterations(20).with_continuation(Continuation::Random).with_seed(9);
+        let mut second = MonteCarloPlayer::new().with_iterations(20).with_continuation(Continuation::Random).with_seed(9);
+        let values = first.evaluate(&observation, &legal);
+        assert_eq!(values, second.evaluate(&observation, &legal));
+        // Folding the small blind loses it whatever the cards
+        assert_eq!(values[0], (Action::Fold, -1.0));
+        // With a time limit of nothing every action is still tried once
+        let mut hurried = MonteCarloPlayer::new().with_time_limit(Duration::ZERO).with_seed(9);
+        assert_eq!(hurried.evaluate(&observation, &legal).len(), values.len());
+    }
+    #[test]
+    fn test_thinking_leaves_time_on_the_clock() {
+        // 20 of the 40 seconds are gone when it is asked, it thinks for half of what is left
+        let clock = ManualClock::new();
+        let mut timer = ActionTimer::new(TimeControl { per_action: Duration::from_secs(10), time_bank: Duration::from_secs(30) }).with_clock(Box::new(clock.clone()));
+        timer.add_seat();
+        timer.add_seat();
+        timer.start(0);
+        clock.advance(Duration::from_secs(20));
+        let state = holdem(2, "7c 2d 8s 3h 9c Jh 4s Kd 5c");
+        let observation = Observation { time_left: Some(timer.time_left(0)), ..Observation::new(&state, 0) };
+        let player = MonteCarloPlayer::new();
+        let thinking = player.thinking_time(&observation).unwrap();
+        assert_eq!(thinking, Duration::from_secs(10));
+        clock.advance(thinking);
+        assert!(!timer.is_out_of_time(0));
+        assert_eq!(timer.time_left(0), Duration::from_secs(10));
+        // Its own limit when that is shorter, and no limit without either
+        assert_eq!(player.with_time_limit(Duration::from_secs(3)).thinking_time(&observation), Some(Duration::from_secs(3)));
+        assert_eq!(MonteCarloPlayer::new().thinking_time(&Observation::new(&state, 0)), None);
+    }
+}
@@ -1,35 +1,47 @@
-pub use crate::players::human_player::HumanPlayer;
-pub use crate::players::ai_player::AIPlayer;
-use crate::players::base::{PlayerAction, PlayerFunctions};
+pub use crate::players::human_player::{prompt, HumanPlayer, Keyboard};
+pub use crate::players::ai_player::{AIPlayer, Style};
+pub use crate::players::bots::{CallStation, Maniac, RandomPlayer, Rock, ScriptedPlayer};
+pub use crate::players::monte_carlo::{Continuation, MonteCarloPlayer};
+use crate::game::config::GameConfig;
+use crate::game::legal::LegalActions;
+use crate::game::observation::Observation;
+use crate::players::base::{Agent, Decision};
 
+// A player at the table, any agent: a person, one of the bots or one registered by another crate
 #[derive(Debug)]
-pub enum PlayerType {
-    HumanPlayer(HumanPlayer),
-    AIPlayer(AIPlayer),
+pub struct Player {
+    agent: Box<dyn Agent>,
 }
 
-impl PlayerType {
-    pub fn new_human_player(name: String, chips: u32) -> PlayerType {
-        PlayerType::HumanPlayer(HumanPlayer::new(name, chips))
+impl Player {
+    pub fn new(agent: impl Agent + 'static) -> Player {
+        Player { agent: Box::new(agent) }
+    }
+    pub fn from_agent(agent: Box<dyn Agent>) -> Player {
+        Player { agent }
+    }
+    pub fn new_human_player(name: String) -> Player {
+        Player::new(HumanPlayer::new(name))
+    }
+    pub fn new_ai_player() -> Player {
+        Player::new(AIPlayer::new())
     }
     pub fn get_name(&self) -> &str {
-        match self {
-            PlayerType::HumanPlayer(player)=> player.get_name(),
-            PlayerType::AIPlayer(_) => "AI Player",
-        }
-    }
-    pub fn get_action<T: PlayerFunctions>(&mut self, current_bet: u32) -> PlayerAction {
-        match self {
-            PlayerType::HumanPlayer(player)=> player.get_action(current_bet),
-            PlayerType::AIPlayer(player) => player.get_action(current_bet),
-        }
-    }
-    pub fn get_chips(&self) -> u32 {
-        match self {
-            PlayerType::HumanPlayer(player)=> player.get_chips(),
-            PlayerType::AIPlayer(player) => player.get_chips(),
-        }
+        self.agent.name()
+    }
+    pub fn decide(&mut self, observation: &Observation, legal: &LegalActions) -> Decision {
+        self.agent.decide(observation, legal)
+    }
+    pub fn agree_to_runouts(&mut self, times: usize) -> bool {
+        self.agent.agree_to_runouts(times)
+    }
+    pub fn choose_game(&mut self, games: &[GameConfig]) -> usize {
+        self.agent.choose_game(games)
+    }
+    pub fn agent(&self) -> &dyn Agent {
+        self.agent.as_ref()
+    }
+    pub fn agent_mut(&mut self) -> &mut dyn Agent {
+        self.agent.as_mut()
     }
 }
-
-
@@ -0,0 +1,274 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+use thiserror::Error;
+use crate::players::base::Agent;
+use crate::players::action::Action;
+use crate::players::player::{AIPlayer, CallStation, Continuation, HumanPlayer, Maniac, MonteCarloPlayer, Player, RandomPlayer, Rock, ScriptedPlayer, Style};
+
+// An agent to seat, written as kind or kind:args, e.g. "random", "human:Alice" or "cfr:strategies/leduc.bin"
+// What the args mean is up to the kind, agents with settings take them as key=value pairs, e.g. "ai:tightness=0.2,aggression=0.7"
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AgentSpec {
+    pub kind: String,
+    pub args: String, // Empty when the spec is only a kind
+}
+
+impl AgentSpec {
+    // The key=value pairs of the args in the order given, trimmed
+    pub fn params(&self) -> Result<Vec<(&str, &str)>, RegistryError> {
+        self.args.split(',').filter(|pair| !pair.trim().is_empty()).map(|pair| {
+            let (key, value) = pair.split_once('=').ok_or_else(|| self.invalid(format!("expected key=value, got {}", pair.trim())))?;
+            Ok((key.trim(), value.trim()))
+        }).collect()
+    }
+
+    // Value of key in the key=value args, None if it isn't given
+    pub fn param<T: FromStr>(&self, key: &str) -> Result<Option<T>, RegistryError> {
+        match self.params()?.into_iter().find(|&(name, _)| name == key) {
+            Some((_, value)) => value.parse().map(Some).map_err(|_| self.invalid(format!("invalid value {} for {}", value, key))),
+            None => Ok(None),
+        }
+    }
+
+    // Error for args the kind can't use
+    pub fn invalid(&self, message: impl Into<String>) -> RegistryError {
+        RegistryError::InvalidArgs { kind: self.kind.clone(), message: message.into() }
+    }
+}
+
+impl FromStr for AgentSpec {
+    type Err = RegistryError;
+
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        let (kind, args) = spec.split_once(':').unwrap_or((spec, ""));
+        let kind = kind.trim().to_lowercase();
+        if kind.is_empty() {
+            return Err(RegistryError::Empty);
+        }
+        Ok(AgentSpec { kind, args: args.trim().to_string() })
+    }
+}
+
+impl fmt::Display for AgentSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.args.is_empty() {
+            write!(f, "{}", self.kind)
+        } else {
+            write!(f, "{}:{}", self.kind, self.args)
+        }
+    }
+}
+
+// Makes an agent playing under the given name from a spec of its kind
+pub type AgentConstructor = Box<dyn Fn(&str, &AgentSpec) -> Result<Box<dyn Agent>, RegistryError>>;
+
+// Kinds of agents that can be seated by name, new() knows the agents of this crate
+// and other crates register their own, so a bot can be added to a game without changing this one
+pub struct AgentRegistry {
+    constructors: BTreeMap<String, Registered>, // Sorted so the kinds are listed in order
+}
+
+// A kind of agent and the settings it takes
+struct Registered {
+    keys: Option<Vec<String>>, // Keys of its key=value args, None if the kind reads its args itself
+    constructor: AgentConstructor,
+}
+
+impl AgentRegistry {
+    // Registry with the agents of this crate:
+    // human (args are the name to play under) and ai (tightness and aggression from 0 to 1, samples and seed),
+    // the benchmark bots callstation, maniac, random (seed), rock (seed) and scripted (its actions, e.g. "scripted:call,raise 6,fold"),
+    // and montecarlo (iterations, time in milliseconds, ranges true or false, continuation checkdown or random, and seed)
+    pub fn new() -> Self {
+        let mut registry = AgentRegistry::empty();
+        registry.register_with_args("human", |name, spec| {
+            let name = if spec.args.is_empty() { name } else { &spec.args };
+            Ok(Box::new(HumanPlayer::new(name.to_string())))
+        });
+        registry.register("ai", &["tightness", "aggression", "samples", "seed"], |name, spec| {
+            let defaults = Style::default();
+            let style = Style {
+                tightness: spec.param("tightness")?.unwrap_or(defaults.tightness),
+                aggression: spec.param("aggression")?.unwrap_or(defaults.aggression),
+            };
+            let mut player = AIPlayer::new().with_name(name).with_style(style);
+            if let Some(samples) = spec.param("samples")? {
+                player = player.with_samples(samples);
+            }
+            if let Some(seed) = spec.param("seed")? {
+                player = player.with_seed(seed);
+            }
+            Ok(Box::new(player))
+        });
+        registry.register("callstation", &[], |name, _| Ok(Box::new(CallStation::new().with_name(name))));
+        registry.register("maniac", &[], |name, _| Ok(Box::new(Maniac::new().with_name(name))));
+        registry.register("random", &["seed"], |name, spec| {
+            let player = RandomPlayer::new().with_name(name);
+            Ok(Box::new(match spec.param("seed")? {
+                Some(seed) => player.with_seed(seed),
+                None => player,
+            }))
+        });
+        registry.register("rock", &["seed"], |name, spec| {
+            let player = Rock::new().with_name(name);
+            Ok(Box::new(match spec.param("seed")? {
+                Some(seed) => player.with_seed(seed),
+                None => player,
+            }))
+        });
+        registry.register_with_args("scripted", |name, spec| {
+            let script = spec.args.split(',').filter(|action| !action.trim().is_empty()).map(|action| action.parse::<Action>()).collect::<Result<_, _>>().map_err(|e| spec.invalid(e.to_string()))?;
+            Ok(Box::new(ScriptedPlayer::new(script).with_name(name)))
+        });
+        registry.register("montecarlo", &["iterations", "time", "ranges", "continuation", "seed"], |name, spec| {
+            let mut player = MonteCarloPlayer::new().with_name(name);
+            if let Some(iterations) = spec.param("iterations")? {
+                player = player.with_iterations(iterations);
+            }
+            if let Some(millis) = spec.param("time")? {
+                player = player.with_time_limit(Duration::from_millis(millis));
+            }
+            if let Some(ranges) = spec.param("ranges")? {
+                player = player.with_ranges(ranges);
+            }
+            match spec.param::<String>("continuation")?.as_deref() {
+                None | Some("checkdown") => {}
+                Some("random") => player = player.with_continuation(Continuation::Random),
+                Some(other) => return Err(spec.invalid(format!("unknown continuation {}, expected checkdown or random", other))),
+            }
+            if let Some(seed) = spec.param("seed")? {
+                player = player.with_seed(seed);
+            }
+            Ok(Box::new(player))
+        });
+        registry
+    }
+
+    // Registry without any agents
+    pub fn empty() -> Self {
+        AgentRegistry { constructors: BTreeMap::new() }
+    }
+
+    // Adds a kind of agent taking the key=value settings keys, replacing any agent already registered under the same kind
+    // A spec giving any other key is rejected before the constructor is called
+    pub fn register(&mut self, kind: &str, keys: &[&str], constructor: impl Fn(&str, &AgentSpec) -> Result<Box<dyn Agent>, RegistryError> + 'static) {
+        let keys = Some(keys.iter().map(|key| key.to_string()).collect());
+        self.constructors.insert(kind.to_lowercase(), Registered { keys, constructor: Box::new(constructor) });
+    }
+
+    // Adds a kind of agent whose args aren't key=value settings, e.g. a name or a file, the constructor checks them
+    pub fn register_with_args(&mut self, kind: &str, constructor: impl Fn(&str, &AgentSpec) -> Result<Box<dyn Agent>, RegistryError> + 'static) {
+        self.constructors.insert(kind.to_lowercase(), Registered { keys: None, constructor: Box::new(constructor) });
+    }
+
+    // Kinds that can be seated, in alphabetical order
+    pub fn kinds(&self) -> Vec<&str> {
+        self.constructors.keys().map(|kind| kind.as_str()).collect()
+    }
+
+    pub fn contains(&self, kind: &str) -> bool {
+        self.constructors.contains_key(&kind.to_lowercase())
+    }
+
+    // Makes the player for a spec, name is used by agents that play under the name of their seat
+    pub fn create(&self, spec: &str, name: &str) -> Result<Player, RegistryError> {
+        let spec: AgentSpec = spec.parse()?;
+        let registered = self.constructors.get(&spec.kind).ok_or_else(|| RegistryError::Unknown { kind: spec.kind.clone(), known: self.kinds().join(", ") })?;
+        if let Some(keys) = &registered.keys {
+            if let Some((key, _)) = spec.params()?.into_iter().find(|(key, _)| !keys.iter().any(|known| known == key)) {
+                let known = if keys.is_empty() { String::from("none") } else { keys.join(", ") };
+                return Err(RegistryError::UnknownSetting { kind: spec.kind.clone(), key: key.to_string(), known });
+            }
+        }
+        Ok(Player::from_agent((registered.constructor)(name, &spec)?))
+    }
+}
+
+impl Default for AgentRegistry {
+    fn default() -> Self {
+        AgentRegistry::new()
+    }
+}
+
+impl fmt::Debug for AgentRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AgentRegistry").field("kinds", &self.kinds()).finish()
+    }
+}
+
+// Errors for specs that can't be made into an agent
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum RegistryError {
+    #[error("No agent given")]
+    Empty,
+    #[error("Unknown agent {kind}, expected one of {known}")]
+    Unknown { kind: String, known: String },
+    #[error("Invalid settings for {kind}: {message}")]
+    InvalidArgs { kind: String, message: String },
+    #[error("Unknown setting {key} for {kind}, it takes {known}")]
+    UnknownSetting { kind: String, key: String, known: String },
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::legal::LegalActions;
+    use crate::game::observation::Observation;
+    use crate::players::action::Action;
+    use crate::players::base::Decision;
+
+    // Agent that always folds, registered from outside the built in agents
+    #[derive(Debug)]
+    struct Folder {
+        name: String,
+    }
+
+    impl Agent for Folder {
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        fn decide(&mut self, _observation: &Observation, _legal: &LegalActions) -> Decision {
+            Decision::Act(Action::Fold)
+        }
+    }
+
+    #[test]
+    fn test_specs() {
+        let spec: AgentSpec = "CFR:strategies/leduc.bin".parse().unwrap();
+        assert_eq!(spec, AgentSpec { kind: String::from("cfr"), args: String::from("strategies/leduc.bin") });
+        assert_eq!(spec.to_string(), "cfr:strategies/leduc.bin");
+        let spec: AgentSpec = "ai:tightness=0.2, aggression=0.7".parse().unwrap();
+        assert_eq!(spec.params(), Ok(vec![("tightness", "0.2"), ("aggression", "0.7")]));
+        assert_eq!(spec.param::<f64>("aggression"), Ok(Some(0.7)));
+        assert_eq!(spec.param::<f64>("seed"), Ok(None));
+        assert!(spec.param::<u32>("tightness").is_err());
+        assert!("ai:tightness".parse::<AgentSpec>().unwrap().params().is_err());
+        assert_eq!(":x".parse::<AgentSpec>(), Err(RegistryError::Empty));
+    }
+    #[test]
+    fn test_registry_creates_players() {
+        let mut registry = AgentRegistry::new();
+        assert_eq!(registry.create("human", "Seat 1").unwrap().get_name(), "Seat 1");
+        assert_eq!(registry.create("human:Alice", "Seat 1").unwrap().get_name(), "Alice");
+        assert!(matches!(registry.create("folder", "Seat 2"), Err(RegistryError::Unknown { .. })));
+        registry.register("folder", &[], |name, _| Ok(Box::new(Folder { name: name.to_string() })));
+        assert_eq!(registry.kinds(), vec!["ai", "callstation", "folder", "human", "maniac", "montecarlo", "random", "rock", "scripted"]);
+        assert_eq!(registry.create("Folder", "Seat 2").unwrap().get_name(), "Seat 2");
+        assert!(registry.create("ai:tightness=0.8,aggression=0.2,seed=3", "Seat 3").is_ok());
+        assert!(matches!(registry.create("ai:tightness=high", "Seat 3"), Err(RegistryError::InvalidArgs { .. })));
+        // A misspelt or unknown key is an error rather than quietly left at its default
+        let misspelt = RegistryError::UnknownSetting { kind: String::from("ai"), key: String::from("tight"), known: String::from("tightness, aggression, samples, seed") };
+        assert_eq!(registry.create("ai:tight=0.2,aggression=0.7", "Seat 3").err(), Some(misspelt));
+        assert!(matches!(registry.create("callstation:seed=1", "Seat 3"), Err(RegistryError::UnknownSetting { key, .. }) if key == "seed"));
+        assert!(matches!(registry.create("Folder:speed=2", "Seat 3"), Err(RegistryError::UnknownSetting { .. })));
+        assert_eq!(registry.create("random:seed=1", "Seat 4").unwrap().get_name(), "Seat 4");
+        assert!(registry.create("scripted:call, raise 6, fold", "Seat 5").is_ok());
+        assert!(matches!(registry.create("scripted:call,shove", "Seat 5"), Err(RegistryError::InvalidArgs { .. })));
+        assert!(registry.create("montecarlo:iterations=50,time=100,ranges=false,continuation=random,seed=2", "Seat 6").is_ok());
+        assert!(matches!(registry.create("montecarlo:continuation=fold", "Seat 6"), Err(RegistryError::InvalidArgs { .. })));
+    }
+}
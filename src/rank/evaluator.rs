@@ -0,0 +1,326 @@
+use crate::cards::card::Card;
+use crate::rank::handrank::{Hand, HandRank, LowHand};
+
+// Evaluates exactly five cards
+pub fn evaluate_five(cards: [Card; 5]) -> Hand {
+    // Ranks from highest to lowest
+    let mut ranks: Vec<u8> = cards.iter().map(|card| card.rank()).collect();
+    ranks.sort_unstable_by(|a, b| b.cmp(a));
+
+    let groups = group(&ranks);
+
+    let flush = cards.iter().all(|card| card.suit() == cards[0].suit());
+    // A straight has five different ranks in a row, the wheel A2345 counts the ace as a one
+    let straight_high = if groups.len() == 5 && ranks[0] - ranks[4] == 4 {
+        Some(ranks[0])
+    } else if ranks == [14, 5, 4, 3, 2] {
+        Some(5)
+    } else {
+        None
+    };
+
+    // Tie breaking ranks are the group ranks in order of importance
+    let mut values = [0u8; 5];
+    for (value, (_, rank)) in values.iter_mut().zip(&groups) {
+        *value = *rank;
+    }
+
+    let rank = match (straight_high, flush, groups[0].0, groups.get(1).map(|group| group.0)) {
+        (Some(14), true, _, _) => HandRank::RoyalFlush,
+        (Some(_), true, _, _) => HandRank::StraightFlush,
+        (_, _, 4, _) => HandRank::FourOfAKind,
+        (_, _, 3, Some(2)) => HandRank::FullHouse,
+        (_, true, _, _) => HandRank::Flush,
+        (Some(_), _, _, _) => HandRank::Straight,
+        (_, _, 3, _) => HandRank::ThreeOfAKind,
+        (_, _, 2, Some(2)) => HandRank::TwoPair,
+        (_, _, 2, _) => HandRank::OnePair,
+        _ => HandRank::HighCard,
+    };
+    // Straights are only decided by their top card
+    if let Some(high) = straight_high {
+        values = [high, 0, 0, 0, 0];
+    }
+    Hand::new(cards, rank, values)
+}
+
+// Best five card hand that can be made from five or more cards, None if there are fewer than five
+pub fn best_hand(cards: &[Card]) -> Option<Hand> {
+    combinations(cards, 5)
+        .into_iter()
+        .map(|five| evaluate_five([five[0], five[1], five[2], five[3], five[4]]))
+        .max()
+}
+
+// Best Omaha hand, which has to use exactly two hole cards and three board cards
+pub fn best_omaha_hand(hole: &[Card], board: &[Card]) -> Option<Hand> {
+    let mut best: Option<Hand> = None;
+    for two in combinations(hole, 2) {
+        for three in combinations(board, 3) {
+            let hand = evaluate_five([two[0], two[1], three[0], three[1], three[2]]);
+            if best.is_none_or(|best| hand > best) {
+                best = Some(hand);
+            }
+        }
+    }
+    best
+}
+
+// Groups equal values, the biggest groups first and the higher value first among groups of the same size
+fn group(values: &[u8]) -> Vec<(usize, u8)> {
+    let mut groups: Vec<(usize, u8)> = Vec::with_capacity(values.len());
+    for &value in values {
+        match groups.iter_mut().find(|(_, v)| *v == value) {
+            Some(group) => group.0 += 1,
+            None => groups.push((1, value)),
+        }
+    }
+    groups.sort_unstable_by(|a, b| b.cmp(a));
+    groups
+}
+
+// Pair category of grouped values, straights and flushes are never considered
+fn pair_rank(groups: &[(usize, u8)]) -> HandRank {
+    match (groups.first().map_or(0, |group| group.0), groups.get(1).map(|group| group.0)) {
+        (4, _) => HandRank::FourOfAKind,
+        (3, Some(2)) => HandRank::FullHouse,
+        (3, _) => HandRank::ThreeOfAKind,
+        (2, Some(2)) => HandRank::TwoPair,
+        (2, _) => HandRank::OnePair,
+        _ => HandRank::HighCard,
+    }
+}
+
+// Evaluates a hand of fewer than five cards where only pairs and high cards count (Kuhn and Leduc)
+pub fn evaluate_small(cards: &[Card]) -> Option<Hand> {
+    let (rank, values) = showing_strength(cards);
+    Hand::partial(cards, rank, values)
+}
+
+// Strength of the face up cards in stud, only pairs, trips and quads count and then the high cards
+// Used to decide who acts first, works for any number of cards
+pub fn showing_strength(cards: &[Card]) -> (HandRank, [u8; 5]) {
+    let ranks: Vec<u8> = cards.iter().map(|card| card.rank()).collect();
+    let groups = group(&ranks);
+    let mut values = [0u8; 5];
+    for (value, (_, rank)) in values.iter_mut().zip(&groups) {
+        *value = *rank;
+    }
+    (pair_rank(&groups), values)
+}
+
+// Strength of the face up cards in razz, aces count as one and the lowest cards showing are the best
+// Smaller is better, pairs are worse than no pair whatever the ranks
+pub fn showing_low_strength(cards: &[Card]) -> (HandRank, [u8; 5]) {
+    let ranks: Vec<u8> = cards.iter().map(|card| if card.rank() == 14 { 1 } else { card.rank() }).collect();
+    let groups = group(&ranks);
+    let mut values = [0u8; 5];
+    for (value, (_, rank)) in values.iter_mut().zip(&groups) {
+        *value = *rank;
+    }
+    (pair_rank(&groups), values)
+}
+
+// Evaluates exactly five cards as an ace-to-five low
+pub fn evaluate_low_five(cards: [Card; 5]) -> LowHand {
+    let ranks: Vec<u8> = cards.iter().map(|card| if card.rank() == 14 { 1 } else { card.rank() }).collect();
+    let groups = group(&ranks);
+    let mut values = [0u8; 5];
+    for (value, (_, rank)) in values.iter_mut().zip(&groups) {
+        *value = *rank;
+    }
+    LowHand::new(cards, pair_rank(&groups), values)
+}
+
+// Best ace-to-five low from five or more cards
+// With a qualifier only five different ranks no higher than it count (8 for eight or better), None if there is no such low
+pub fn best_low_hand(cards: &[Card], qualifier: Option<u8>) -> Option<LowHand> {
+    combinations(cards, 5)
+        .into_iter()
+        .map(|five| evaluate_low_five([five[0], five[1], five[2], five[3], five[4]]))
+        .filter(|low| qualifier.is_none_or(|q| low.rank() == HandRank::HighCard && low.values()[0] <= q))
+        .max()
+}
+
+// Best ace-to-five low in Omaha, made from exactly two hole cards and three board cards
+pub fn best_omaha_low(hole: &[Card], board: &[Card], qualifier: Option<u8>) -> Option<LowHand> {
+    let mut best: Option<LowHand> = None;
+    for two in combinations(hole, 2) {
+        for three in combinations(board, 3) {
+            if let Some(low) = best_low_hand(&[two.as_slice(), &three].concat(), qualifier) {
+                if best.is_none_or(|best| low > best) {
+                    best = Some(low);
+                }
+            }
+        }
+    }
+    best
+}
+
+// Evaluates exactly five cards as a deuce-to-seven low, the best hand is 7-5-4-3-2 in different suits
+// Aces are always high so A2345 is not a straight, straights and flushes count just like in a high hand
+pub fn evaluate_deuce_to_seven(cards: [Card; 5]) -> LowHand {
+    let hand = evaluate_five(cards);
+    let mut ranks: Vec<u8> = cards.iter().map(|card| card.rank()).collect();
+    ranks.sort_unstable_by(|a, b| b.cmp(a));
+    let flush = cards.iter().all(|card| card.suit() == cards[0].suit());
+    let (rank, values) = match hand.rank() {
+        HandRank::Straight | HandRank::StraightFlush | HandRank::RoyalFlush => {
+            let rank = if ranks == [14, 5, 4, 3, 2] {
+                if flush { HandRank::Flush } else { HandRank::HighCard }
+            } else {
+                hand.rank()
+            };
+            (rank, [ranks[0], ranks[1], ranks[2], ranks[3], ranks[4]])
+        }
+        rank => (rank, *hand.values()),
+    };
+    LowHand::new(cards, rank, values)
+}
+
+// Best deuce-to-seven low from five or more cards
+pub fn best_deuce_to_seven(cards: &[Card]) -> Option<LowHand> {
+    combinations(cards, 5)
+        .into_iter()
+        .map(|five| evaluate_deuce_to_seven([five[0], five[1], five[2], five[3], five[4]]))
+        .max()
+}
+
+// Every way of choosing k cards from the given cards
+pub fn combinations(cards: &[Card], k: usize) -> Vec<Vec<Card>> {
+    if k == 0 {
+        return vec![Vec::new()];
+    }
+    if cards.len() < k {
+        return Vec::new();
+    }
+    // Either the first card is in the combination or it is not
+    let mut result: Vec<Vec<Card>> = combinations(&cards[1..], k - 1)
+        .into_iter()
+        .map(|mut rest| {
+            rest.insert(0, cards[0]);
+            rest
+        })
+        .collect();
+    result.extend(combinations(&cards[1..], k));
+    result
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::card::parse_cards;
+
+    fn hand(cards: &str) -> Hand {
+        best_hand(&parse_cards(cards).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_hand_categories() {
+        assert_eq!(hand("As Ks Qs Js Ts").rank(), HandRank::RoyalFlush);
+        assert_eq!(hand("9h 8h 7h 6h 5h").rank(), HandRank::StraightFlush);
+        assert_eq!(hand("9h 9d 9c 9s 5h").rank(), HandRank::FourOfAKind);
+        assert_eq!(hand("9h 9d 9c 5s 5h").rank(), HandRank::FullHouse);
+        assert_eq!(hand("Kh 9h 7h 6h 2h").rank(), HandRank::Flush);
+        assert_eq!(hand("9h 8d 7c 6s 5h").rank(), HandRank::Straight);
+        assert_eq!(hand("Ah 2d 3c 4s 5h").rank(), HandRank::Straight);
+        assert_eq!(hand("9h 9d 9c 6s 5h").rank(), HandRank::ThreeOfAKind);
+        assert_eq!(hand("9h 9d 6c 6s 5h").rank(), HandRank::TwoPair);
+        assert_eq!(hand("9h 9d 7c 6s 5h").rank(), HandRank::OnePair);
+        assert_eq!(hand("Kh 9d 7c 6s 5h").rank(), HandRank::HighCard);
+    }
+    #[test]
+    fn test_hand_comparisons() {
+        assert!(hand("As Ad Kc Qs Jh") > hand("Ks Kd Ac Qs Jh"));
+        assert!(hand("As Ad Kc Qs Jh") > hand("Ah Ac Kd Qs Th"));
+        assert!(hand("6h 5d 4c 3s 2h") > hand("Ah 2d 3c 4s 5h")); // The wheel is the lowest straight
+        assert!(hand("2h 2d 2c 3s 3h") > hand("Ah Kh 9h 7h 5h"));
+        assert!(hand("Kh Kd 2c 2s 3h") > hand("Qh Qd Jc Js Ah"));
+        assert_eq!(hand("As Kd Qc Js 9h"), hand("Ah Kc Qd Jh 9s")); // Suits never break ties
+    }
+    #[test]
+    fn test_best_of_seven() {
+        let best = hand("As Ks 2d 7s 9s Qs 2c");
+        assert_eq!(best.rank(), HandRank::Flush);
+        assert_eq!(best.values(), &[14, 13, 12, 9, 7]);
+    }
+    #[test]
+    fn test_omaha_uses_two_hole_cards() {
+        // Four spades in hand and one on the board is not a flush in Omaha
+        let hole = parse_cards("As Ks Qs Js").unwrap();
+        let board = parse_cards("2s 7d 8c 9h 3d").unwrap();
+        let omaha = best_omaha_hand(&hole, &board).unwrap();
+        assert_eq!(omaha.rank(), HandRank::HighCard);
+        // Holdem rules would make a flush with the same cards
+        assert!(best_hand(&[hole, board].concat()).unwrap().rank() == HandRank::Flush);
+    }
+    #[test]
+    fn test_eight_or_better_low() {
+        let low = |cards: &str| best_low_hand(&parse_cards(cards).unwrap(), Some(8));
+        // Straights and flushes do not hurt a low, aces are low
+        assert_eq!(low("As 2s 3s 4s 5s Kd Kc").unwrap().values(), &[5, 4, 3, 2, 1]);
+        assert!(low("8h 7d 6c 5s 3h").unwrap() < low("7h 6d 4c 3s 2h").unwrap());
+        assert!(low("8h 6d 4c 3s 2h").unwrap() < low("8h 5d 4c 3s 2h").unwrap());
+        // No five different ranks of eight or lower, no low
+        assert!(low("9h 7d 6c 5s 3h Kd").is_none());
+        assert!(low("As Ad 2c 2s 3h 3d 4c").is_none());
+    }
+    #[test]
+    fn test_low_without_qualifier_counts_pairs() {
+        // Four ranks can't make five different cards, one pair is the best low
+        let low = best_low_hand(&parse_cards("Ks Kd Qc Qs Jh Jd Tc").unwrap(), None).unwrap();
+        assert_eq!(low.rank(), HandRank::OnePair);
+        assert!(low > best_low_hand(&parse_cards("Ks Kd Kc Qs Jh Jd Jc").unwrap(), None).unwrap());
+    }
+    #[test]
+    fn test_omaha_low_uses_two_hole_cards() {
+        // Only one low card in hand, no low even with four low cards on the board
+        let board = parse_cards("2s 3d 4c 8h Kd").unwrap();
+        assert!(best_omaha_low(&parse_cards("As Kh Qs Qd").unwrap(), &board, Some(8)).is_none());
+        let low = best_omaha_low(&parse_cards("As 5h Ks 2d").unwrap(), &board, Some(8)).unwrap();
+        assert_eq!(low.values(), &[5, 4, 3, 2, 1]);
+    }
+    #[test]
+    fn test_deuce_to_seven() {
+        let low = |cards: &str| best_deuce_to_seven(&parse_cards(cards).unwrap()).unwrap();
+        assert_eq!(low("7h 5d 4c 3s 2h").rank(), HandRank::HighCard);
+        assert!(low("7h 5d 4c 3s 2h") > low("7h 6d 4c 3s 2h"));
+        // Aces are high, A2345 is an ace high hand and not a straight
+        assert_eq!(low("As 2d 3c 4s 5h").rank(), HandRank::HighCard);
+        assert!(low("Kh 8d 6c 4s 3h") > low("As 2d 3c 4s 5h"));
+        // Straights, flushes and pairs all lose to any high card hand
+        assert!(low("Kh Qd Jc 9s 2h") > low("8h 7d 6c 5s 4h"));
+        assert!(low("Kh Qd Jc 9s 2h") > low("7h 5h 4h 3h 2h"));
+        assert!(low("2h 2d 3c 4s 5h") > low("8h 7d 6c 5s 4h"));
+    }
+    #[test]
+    fn test_small_hands() {
+        let small = |cards: &str| evaluate_small(&parse_cards(cards).unwrap()).unwrap();
+        assert!(small("Ks") > small("Qs"));
+        // Leduc, a pair with the board beats a higher card
+        assert!(small("Jh Js") > small("Ks Jh"));
+        assert!(small("Kh Qs") > small("Jh Qs"));
+        assert_eq!(small("Kh Qs").cards().len(), 2);
+        assert!(evaluate_small(&[]).is_none());
+    }
+    #[test]
+    fn test_showing_strength() {
+        let showing = |cards: &str| showing_strength(&parse_cards(cards).unwrap());
+        assert!(showing("2s 2d") > showing("As Kd"));
+        assert!(showing("As 3d") > showing("Ks Qd"));
+        assert!(showing("9s 9d 3c") > showing("9h 9c 2d"));
+        assert_eq!(showing("Ks 7d").0, HandRank::HighCard);
+        // In razz the lowest cards are best and aces are low
+        let low = |cards: &str| showing_low_strength(&parse_cards(cards).unwrap());
+        assert!(low("As 3d") < low("2s 4d"));
+        assert!(low("Ks Qd") < low("2s 2d"));
+    }
+    #[test]
+    fn test_combinations() {
+        let cards = parse_cards("As Ks Qs Js Ts 9s 8s").unwrap();
+        assert_eq!(combinations(&cards, 5).len(), 21);
+        assert_eq!(combinations(&cards, 2).len(), 21);
+        assert_eq!(combinations(&cards[..2], 3).len(), 0);
+    }
+}
@@ -1,6 +1,10 @@
 use crate::cards::card::Card;
+use std::cmp::Ordering;
+use std::fmt;
 
-enum HandRank {
+// Categories of five card poker hands, ordered from weakest to strongest so they can be compared with < and >
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum HandRank {
     HighCard,
     OnePair,
     TwoPair,
@@ -13,7 +17,147 @@ enum HandRank {
     RoyalFlush,
 }
 
-struct Hand {
+impl fmt::Display for HandRank {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            HandRank::HighCard => "High Card",
+            HandRank::OnePair => "One Pair",
+            HandRank::TwoPair => "Two Pair",
+            HandRank::ThreeOfAKind => "Three of a Kind",
+            HandRank::Straight => "Straight",
+            HandRank::Flush => "Flush",
+            HandRank::FullHouse => "Full House",
+            HandRank::FourOfAKind => "Four of a Kind",
+            HandRank::StraightFlush => "Straight Flush",
+            HandRank::RoyalFlush => "Royal Flush",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// An evaluated five card hand, or a smaller one in games dealt fewer cards (Kuhn, Leduc)
+// values holds the ranks that decide ties in order of importance, e.g. a full house KKK77 is [13, 7, 0, 0, 0]
+#[derive(Debug, Clone, Copy)]
+pub struct Hand {
     cards: [Card; 5], // initializing a array of 5 cards
+    len: usize,       // Number of cards in the hand, the rest of the array is unused
     rank: HandRank,
-}
\ No newline at end of file
+    values: [u8; 5],
+}
+
+impl Hand {
+    pub fn new(cards: [Card; 5], rank: HandRank, values: [u8; 5]) -> Self {
+        Hand { cards, len: 5, rank, values }
+    }
+    // A hand of one to five cards, None if there are no cards or more than five
+    pub fn partial(cards: &[Card], rank: HandRank, values: [u8; 5]) -> Option<Self> {
+        let first = *cards.first()?;
+        if cards.len() > 5 {
+            return None;
+        }
+        let mut array = [first; 5];
+        array[..cards.len()].copy_from_slice(cards);
+        Some(Hand { cards: array, len: cards.len(), rank, values })
+    }
+    // The cards making up the hand
+    pub fn cards(&self) -> &[Card] {
+        &self.cards[..self.len]
+    }
+    // The category of the hand
+    pub fn rank(&self) -> HandRank {
+        self.rank
+    }
+    // Tie breaking ranks
+    pub fn values(&self) -> &[u8; 5] {
+        &self.values
+    }
+}
+
+// Hands are compared on category first then on the tie breaking ranks, suits never matter
+impl Ord for Hand {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.rank.cmp(&other.rank).then(self.values.cmp(&other.values))
+    }
+}
+
+impl PartialOrd for Hand {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Hand {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Hand {}
+
+impl fmt::Display for Hand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cards: Vec<String> = self.cards().iter().map(|card| card.to_string()).collect();
+        write!(f, "{} ({})", self.rank, cards.join(" "))
+    }
+}
+
+// An evaluated low hand, either ace-to-five (aces count as one, straights and flushes do not count)
+// or deuce-to-seven (aces are high, straights and flushes count against the hand)
+// values holds the ranks from the most to the least important, the lower the better,
+// so that Ord works like for Hand the better low compares as greater
+#[derive(Debug, Clone, Copy)]
+pub struct LowHand {
+    cards: [Card; 5],
+    rank: HandRank,  // HighCard is a hand without a pair (or straight or flush in deuce-to-seven)
+    values: [u8; 5],
+}
+
+impl LowHand {
+    pub fn new(cards: [Card; 5], rank: HandRank, values: [u8; 5]) -> Self {
+        LowHand { cards, rank, values }
+    }
+    // The five cards making up the hand
+    pub fn cards(&self) -> &[Card; 5] {
+        &self.cards
+    }
+    // Pairs (or worse) in the hand, HighCard if there are none
+    pub fn rank(&self) -> HandRank {
+        self.rank
+    }
+    // The ranks from the most important, ace as one, e.g. 7-5-4-2-A is [7, 5, 4, 2, 1]
+    pub fn values(&self) -> &[u8; 5] {
+        &self.values
+    }
+}
+
+// Lower category and lower ranks are better, reversed so the best low is the greatest
+impl Ord for LowHand {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.rank.cmp(&self.rank).then(other.values.cmp(&self.values))
+    }
+}
+
+impl PartialOrd for LowHand {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for LowHand {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for LowHand {}
+
+impl fmt::Display for LowHand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.rank != HandRank::HighCard {
+            let cards: Vec<String> = self.cards.iter().map(|card| card.to_string()).collect();
+            return write!(f, "{} ({})", self.rank, cards.join(" "));
+        }
+        let values: Vec<String> = self.values.iter().map(|value| value.to_string()).collect();
+        write!(f, "{} low", values.join("-"))
+    }
+}
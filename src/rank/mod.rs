@@ -0,0 +1,2 @@
+pub mod handrank;
+pub mod evaluator;
@@ -0,0 +1,20 @@
+// Helpers shared by the unit tests
+use crate::cards::deck::Deck;
+use crate::game::config::GameConfig;
+use crate::game::observation::Observation;
+use crate::game::state::GameState;
+use crate::game::variant::PokerVariant;
+use crate::players::action::Action;
+use crate::players::base::Agent;
+
+// Hold'em with blinds 1/2, stacks of 100 and the button in seat 0, dealt from the cards in the order written
+// starting left of the button, so heads up seat 0 gets the second and fourth cards
+pub fn holdem(players: usize, cards: &str) -> GameState {
+    GameState::new(GameConfig::new(PokerVariant::TexasHoldem, 1, 2), vec![100; players], 0, Deck::stacked(cards)).unwrap()
+}
+
+// What the agent to act plays, from what its seat can see
+pub fn decide(agent: &mut dyn Agent, state: &GameState) -> Action {
+    let legal = state.legal_actions().unwrap();
+    agent.decide(&Observation::new(state, legal.seat), &legal).action(&legal)
+}